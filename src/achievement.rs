@@ -0,0 +1,89 @@
+use crate::player::PlayerData;
+use crate::prop::{Cabbage, Golden_Clover, Growth_Elixir, Healing_Potion, Honey};
+use serde::Serialize;
+
+// 成就统计的计数类型
+pub const ACHIEVEMENT_GOLD_COUNT: u64 = 1; // 累计收集金币
+pub const ACHIEVEMENT_CLEAN_COUNT: u64 = 2; // 累计清洁次数
+pub const ACHIEVEMENT_FEED_COUNT: u64 = 3; // 累计喂食次数
+pub const ACHIEVEMENT_HEALTH_COUNT: u64 = 4; // 累计治疗次数
+pub const ACHIEVEMENT_ADULT_COUNT: u64 = 5; // 累计养成年的精灵
+pub const ACHIEVEMENT_GRADE5_COUNT: u64 = 6; // 当前拥有的 5 星精灵
+
+// 奖励类型
+pub const REWARD_GOLD: u64 = 1; // 奖励金币
+pub const REWARD_PROP: u64 = 2; // 奖励道具
+
+// 成就，id 对应玩家成就位图中的位置，最多 64 个
+#[derive(Clone, Debug, Serialize)]
+pub struct Achievement {
+    pub id: u64,
+    pub name: &'static str,
+    pub counter_type: u64, // 计数类型
+    pub threshold: u64,    // 达成需要的数量
+    pub reward_type: u64,  // 奖励类型
+    pub reward_value: u64, // 金币数量或道具类型
+    pub reward_count: u64, // 道具数量，奖励金币时为 1
+}
+
+impl Achievement {
+    pub fn new(
+        id: u64,
+        name: &'static str,
+        counter_type: u64,
+        threshold: u64,
+        reward_type: u64,
+        reward_value: u64,
+        reward_count: u64,
+    ) -> Self {
+        Achievement {
+            id,
+            name,
+            counter_type,
+            threshold,
+            reward_type,
+            reward_value,
+            reward_count,
+        }
+    }
+
+    pub fn get_all_achievements() -> &'static Vec<Achievement> {
+        &*ACHIEVEMENT_LIST
+    }
+
+    pub fn get_achievement_by_id(id: u64) -> Option<&'static Achievement> {
+        ACHIEVEMENT_LIST.iter().find(|a| a.id == id)
+    }
+
+    // 成就在位图中的位置
+    pub fn mask(&self) -> u64 {
+        1 << self.id
+    }
+
+    // 玩家是否达成
+    pub fn is_reached(&self, player: &PlayerData) -> bool {
+        let value = match self.counter_type {
+            ACHIEVEMENT_GOLD_COUNT => player.gold_count,
+            ACHIEVEMENT_CLEAN_COUNT => player.clean_count,
+            ACHIEVEMENT_FEED_COUNT => player.feed_count,
+            ACHIEVEMENT_HEALTH_COUNT => player.health_count,
+            ACHIEVEMENT_ADULT_COUNT => player.adult_count,
+            ACHIEVEMENT_GRADE5_COUNT => player.get_elf_count_by_grade(5),
+            _ => 0,
+        };
+        value >= self.threshold
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref ACHIEVEMENT_LIST: Vec<Achievement> = vec![
+        Achievement::new(0, "First Harvest", ACHIEVEMENT_GOLD_COUNT, 1000, REWARD_GOLD, 200, 1),
+        Achievement::new(1, "Gold Rush", ACHIEVEMENT_GOLD_COUNT, 100000, REWARD_GOLD, 5000, 1),
+        Achievement::new(2, "Tidy Ranch", ACHIEVEMENT_CLEAN_COUNT, 50, REWARD_PROP, Cabbage.0, 5),
+        Achievement::new(3, "Caretaker", ACHIEVEMENT_FEED_COUNT, 100, REWARD_PROP, Honey.0, 3),
+        Achievement::new(4, "Healer", ACHIEVEMENT_HEALTH_COUNT, 20, REWARD_PROP, Healing_Potion.0, 3),
+        Achievement::new(5, "Grown Up", ACHIEVEMENT_ADULT_COUNT, 1, REWARD_GOLD, 500, 1),
+        Achievement::new(6, "Breeder", ACHIEVEMENT_ADULT_COUNT, 10, REWARD_PROP, Growth_Elixir.0, 2),
+        Achievement::new(7, "Star Keeper", ACHIEVEMENT_GRADE5_COUNT, 1, REWARD_PROP, Golden_Clover.0, 2),
+    ];
+}
@@ -0,0 +1,79 @@
+use serde::Serialize;
+use zkwasm_rest_abi::MERKLE_MAP;
+
+// 玩家动态记录的 key 前缀，[ACTIVITY_KEY_TAG, pid[0], pid[1], 0]
+const ACTIVITY_KEY_TAG: u64 = 0xa0;
+// 每个玩家最多保留的动态数量，超出后覆盖最早的记录
+const ACTIVITY_CAPACITY: u64 = 32;
+
+// 动态类型
+pub const ACTIVITY_BUY_ELF: u64 = 1; // 购买精灵：牧场id，精灵id，品质
+pub const ACTIVITY_COLLECT_GOLD: u64 = 2; // 收集金币：牧场id，精灵id，金币
+pub const ACTIVITY_SELL_ELF: u64 = 3; // 卖出精灵：牧场id，精灵id，价格
+pub const ACTIVITY_BUY_PROP: u64 = 4; // 购买道具：道具类型，数量，总价
+pub const ACTIVITY_USE_PROP: u64 = 5; // 消耗道具：牧场id，精灵id，道具类型
+pub const ACTIVITY_BUY_SLOT: u64 = 6; // 购买槽位：牧场id，槽位数量，价格
+pub const ACTIVITY_WITHDRAW: u64 = 7; // 提现：金额，手续费
+pub const ACTIVITY_DEPOSIT: u64 = 8; // 充值：充值类型，金币数量或道具类型，数量
+pub const ACTIVITY_ELF_ADULT: u64 = 9; // 精灵成年：牧场id，精灵id
+pub const ACTIVITY_ELF_SICK: u64 = 10; // 精灵健康度归零：牧场id，精灵id
+pub const ACTIVITY_UPKEEP_UNPAID: u64 = 11; // 牧场维护费未支付：牧场id，维护费
+pub const ACTIVITY_CLAIM_ACHIEVEMENT: u64 = 12; // 领取成就奖励：成就id，奖励类型，金币数量或道具类型
+pub const ACTIVITY_CLAIM_QUEST: u64 = 13; // 领取每日任务奖励：任务id，奖励类型，金币数量或道具类型
+pub const ACTIVITY_CHECK_IN: u64 = 14; // 签到：连续天数，奖励类型，金币数量或道具类型
+pub const ACTIVITY_UPGRADE_RANCH: u64 = 15; // 升级牧场：牧场id，新等级，价格
+
+// 玩家的一条动态
+#[derive(Clone, Debug, Serialize)]
+pub struct Activity {
+    pub kind: u64,      // 动态类型
+    pub counter: u64,   // 发生时事件队列的 counter
+    pub args: [u64; 3], // 动态参数，含义由类型决定
+}
+
+impl Activity {
+    fn to_key(pid: &[u64; 2]) -> [u64; 4] {
+        [ACTIVITY_KEY_TAG, pid[0], pid[1], 0]
+    }
+
+    // 记录一条动态，数据格式为 [总数量, 记录...]，第 n 条记录放在 n % ACTIVITY_CAPACITY
+    pub fn record(pid: &[u64; 2], kind: u64, counter: u64, args: [u64; 3]) {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let key = Activity::to_key(pid);
+        let mut data = kvpair.get(&key);
+        if data.is_empty() {
+            data.push(0);
+        }
+        let total = data[0];
+        let slot = (1 + (total % ACTIVITY_CAPACITY) * 5) as usize;
+        let entry = [kind, counter, args[0], args[1], args[2]];
+        if slot < data.len() {
+            data[slot..slot + 5].copy_from_slice(&entry);
+        } else {
+            data.extend_from_slice(&entry);
+        }
+        data[0] = total + 1;
+        kvpair.set(&key, data.as_slice());
+    }
+
+    // 获取玩家最近的动态，最新的在前
+    pub fn get_activities(pid: &[u64; 2]) -> Vec<Activity> {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let data = kvpair.get(&Activity::to_key(pid));
+        if data.is_empty() {
+            return vec![];
+        }
+        let total = data[0];
+        let count = total.min(ACTIVITY_CAPACITY);
+        let mut activities = Vec::with_capacity(count as usize);
+        for n in (total - count..total).rev() {
+            let slot = (1 + (n % ACTIVITY_CAPACITY) * 5) as usize;
+            activities.push(Activity {
+                kind: data[slot],
+                counter: data[slot + 1],
+                args: [data[slot + 2], data[slot + 3], data[slot + 4]],
+            });
+        }
+        activities
+    }
+}
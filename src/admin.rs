@@ -0,0 +1,101 @@
+use crate::config::ADMIN_PUBKEY;
+use crate::player::ElfPlayer;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::slice::IterMut;
+
+// 管理员角色，按位组合
+pub const ROLE_TICKER: u64 = 1; // 推进事件队列
+pub const ROLE_DEPOSITOR: u64 = 2; // 处理 L1 充值
+pub const ROLE_MODERATOR: u64 = 4; // 冻结玩家、调整余额、移除精灵
+pub const ROLE_CONFIG_MANAGER: u64 = 8; // 修改赛季和配置
+pub const ROLE_ALL: u64 = ROLE_TICKER | ROLE_DEPOSITOR | ROLE_MODERATOR | ROLE_CONFIG_MANAGER;
+
+// 注册的管理员，按 pid 记录
+#[derive(Clone, Debug, Serialize)]
+pub struct AdminEntry {
+    pub pid: [u64; 2],
+    pub roles: u64,
+}
+
+// 管理员列表，编译进来的 ADMIN_PUBKEY 是根管理员，拥有所有角色，
+// 不在列表中保存，不能被撤销，只有它可以添加和撤销其他管理员
+pub struct AdminRegistry(RefCell<Vec<AdminEntry>>);
+unsafe impl Sync for AdminRegistry {}
+
+lazy_static::lazy_static! {
+    pub static ref ADMIN_REGISTRY: AdminRegistry = AdminRegistry(RefCell::new(vec![]));
+}
+
+impl AdminRegistry {
+    pub fn is_root(pkey: &[u64; 4]) -> bool {
+        *pkey == *ADMIN_PUBKEY
+    }
+
+    pub fn get_root_pid() -> [u64; 2] {
+        ElfPlayer::pkey_to_pid(&ADMIN_PUBKEY)
+    }
+
+    // 签名的公钥拥有的角色
+    pub fn get_roles(pkey: &[u64; 4]) -> u64 {
+        if AdminRegistry::is_root(pkey) {
+            return ROLE_ALL;
+        }
+        let pid = ElfPlayer::pkey_to_pid(pkey);
+        ADMIN_REGISTRY
+            .0
+            .borrow()
+            .iter()
+            .find(|a| a.pid == pid)
+            .map_or(0, |a| a.roles)
+    }
+
+    // 添加管理员或修改已有管理员的角色
+    pub fn add(pid: [u64; 2], roles: u64) -> bool {
+        if roles == 0 || roles & !ROLE_ALL != 0 || pid == AdminRegistry::get_root_pid() {
+            return false;
+        }
+        let mut entries = ADMIN_REGISTRY.0.borrow_mut();
+        entries.retain(|a| a.pid != pid);
+        entries.push(AdminEntry { pid, roles });
+        true
+    }
+
+    pub fn revoke(pid: &[u64; 2]) -> bool {
+        let mut entries = ADMIN_REGISTRY.0.borrow_mut();
+        let len = entries.len();
+        entries.retain(|a| a.pid != *pid);
+        entries.len() != len
+    }
+
+    // 根管理员在前
+    pub fn get_all() -> Vec<AdminEntry> {
+        let mut admins = vec![AdminEntry {
+            pid: AdminRegistry::get_root_pid(),
+            roles: ROLE_ALL,
+        }];
+        admins.extend(ADMIN_REGISTRY.0.borrow().iter().cloned());
+        admins
+    }
+
+    pub fn to_data(data: &mut Vec<u64>) {
+        let entries = ADMIN_REGISTRY.0.borrow();
+        data.push(entries.len() as u64);
+        for entry in entries.iter() {
+            data.push(entry.pid[0]);
+            data.push(entry.pid[1]);
+            data.push(entry.roles);
+        }
+    }
+
+    pub fn from_data(u64data: &mut IterMut<u64>) {
+        let count = u64data.next().map_or(0, |v| *v);
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let pid = [*u64data.next().unwrap(), *u64data.next().unwrap()];
+            let roles = *u64data.next().unwrap();
+            entries.push(AdminEntry { pid, roles });
+        }
+        *ADMIN_REGISTRY.0.borrow_mut() = entries;
+    }
+}
@@ -0,0 +1,66 @@
+use serde::Serialize;
+use zkwasm_rest_abi::MERKLE_MAP;
+
+// 管理员操作记录的 key 前缀
+const AUDIT_KEY_TAG: u64 = 0xad;
+// 管理员操作记录数量的 key
+const AUDIT_COUNT_KEY: [u64; 4] = [AUDIT_KEY_TAG, 0, 0, 0];
+
+// 管理员操作记录
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditRecord {
+    pub command: u64,    // 管理员命令
+    pub admin: [u64; 2], // 执行操作的管理员
+    pub data: Vec<u64>,  // 命令参数，和交易中的 data 一致（充值命令包含完整的 L1 交易哈希）
+    pub counter: u64,    // 操作时事件队列的 counter
+}
+
+impl AuditRecord {
+    pub fn new(command: u64, admin: [u64; 2], data: Vec<u64>, counter: u64) -> Self {
+        AuditRecord {
+            command,
+            admin,
+            data,
+            counter,
+        }
+    }
+
+    fn to_key(index: u64) -> [u64; 4] {
+        [AUDIT_KEY_TAG, 1, index, 0]
+    }
+
+    // 追加一条记录，命令参数的长度不固定，放在最后
+    pub fn store(&self) {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let count = AuditRecord::get_count();
+        let mut data = vec![self.command, self.admin[0], self.admin[1], self.counter];
+        data.extend_from_slice(&self.data);
+        kvpair.set(&AuditRecord::to_key(count), &data);
+        kvpair.set(&AUDIT_COUNT_KEY, &[count + 1]);
+    }
+
+    // 管理员操作记录数量
+    pub fn get_count() -> u64 {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let data = kvpair.get(&AUDIT_COUNT_KEY);
+        if data.is_empty() {
+            0
+        } else {
+            data[0]
+        }
+    }
+
+    // 按操作顺序获取记录
+    pub fn get_records(start: u64, limit: u64) -> Vec<AuditRecord> {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let end = AuditRecord::get_count().min(start.saturating_add(limit));
+        let mut records = vec![];
+        for index in start..end {
+            let data = kvpair.get(&AuditRecord::to_key(index));
+            if data.len() >= 4 {
+                records.push(AuditRecord::new(data[0], [data[1], data[2]], data[4..].to_vec(), data[3]));
+            }
+        }
+        records
+    }
+}
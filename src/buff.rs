@@ -0,0 +1,95 @@
+use serde::Serialize;
+use std::slice::IterMut;
+use zkwasm_rest_abi::StorageData;
+use crate::prop::{Golden_Clover, Growth_Elixir};
+
+// 增益类型
+pub const BUFF_GOLD: u64 = 1; // 金币产出加成
+pub const BUFF_GROWTH: u64 = 2; // 成长速度加成
+
+// 道具提供的增益参数
+#[derive(Clone, Debug, Serialize)]
+pub struct BuffProp {
+    pub prop_type: u64, // 道具类型
+    pub buff_type: u64, // 增益类型
+    pub modifier: u64,  // 加成系数，以 100 为 1.0 的基准
+    pub duration: u64,  // 持续的 tick 数
+}
+
+impl BuffProp {
+    pub fn new(prop_type: u64, buff_type: u64, modifier: u64, duration: u64) -> Self {
+        BuffProp {
+            prop_type,
+            buff_type,
+            modifier,
+            duration,
+        }
+    }
+
+    pub fn get_all_buff_props() -> &'static Vec<BuffProp> {
+        &*BUFF_PROP_LIST
+    }
+
+    // 根据道具类型返回增益，不是增益道具返回 None
+    pub fn get_buff_by_prop_type(prop_type: u64) -> Option<&'static BuffProp> {
+        BUFF_PROP_LIST.iter().find(|b| b.prop_type == prop_type)
+    }
+}
+
+// 精灵身上生效的增益，保存在牧场中
+#[derive(Clone, Debug, Serialize)]
+pub struct Buff {
+    pub elf_id: u64,    // 精灵id
+    pub buff_type: u64, // 增益类型
+    pub modifier: u64,  // 加成系数
+    pub expire_at: u64, // 过期的 tick
+}
+
+impl Buff {
+    pub fn new(elf_id: u64, buff_type: u64, modifier: u64, expire_at: u64) -> Self {
+        Buff {
+            elf_id,
+            buff_type,
+            modifier,
+            expire_at,
+        }
+    }
+
+    // 当前 tick 是否仍然生效
+    pub fn is_active(&self, counter: u64) -> bool {
+        self.expire_at > counter
+    }
+}
+
+impl StorageData for Buff {
+    fn from_data(u64data: &mut IterMut<u64>) -> Self {
+        let elf_id = *u64data.next().unwrap();
+        let buff_type = *u64data.next().unwrap();
+        let modifier = *u64data.next().unwrap();
+        let expire_at = *u64data.next().unwrap();
+        Buff {
+            elf_id,
+            buff_type,
+            modifier,
+            expire_at,
+        }
+    }
+
+    fn to_data(&self, data: &mut Vec<u64>) {
+        data.push(self.elf_id);
+        data.push(self.buff_type);
+        data.push(self.modifier);
+        data.push(self.expire_at);
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref BUFF_PROP_LIST: Vec<BuffProp> = {
+        vec![
+            // 1 小时内金币产出 x2
+            BuffProp::new(Golden_Clover.0, BUFF_GOLD, 200, 60 * 60 / 5),
+            // 1 小时内成长速度 x1.5
+            BuffProp::new(Growth_Elixir.0, BUFF_GROWTH, 150, 60 * 60 / 5),
+        ]
+    };
+}
@@ -0,0 +1,45 @@
+use crate::achievement::{REWARD_GOLD, REWARD_PROP};
+use crate::prop::{Cabbage, Carrot, Golden_Clover, Healing_Potion};
+use serde::Serialize;
+
+// 签到奖励，按连续签到天数领取
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckInReward {
+    pub streak: u64,       // 连续签到第几天
+    pub reward_type: u64,  // 奖励类型
+    pub reward_value: u64, // 金币数量或道具类型
+    pub reward_count: u64, // 道具数量，奖励金币时为 1
+}
+
+impl CheckInReward {
+    pub fn new(streak: u64, reward_type: u64, reward_value: u64, reward_count: u64) -> Self {
+        CheckInReward {
+            streak,
+            reward_type,
+            reward_value,
+            reward_count,
+        }
+    }
+
+    pub fn get_all_rewards() -> &'static Vec<CheckInReward> {
+        &*CHECK_IN_REWARD_LIST
+    }
+
+    // 根据连续签到天数获取奖励，超过列表长度后循环
+    pub fn get_reward_by_streak(streak: u64) -> &'static CheckInReward {
+        let len = CHECK_IN_REWARD_LIST.len() as u64;
+        &CHECK_IN_REWARD_LIST[((streak.max(1) - 1) % len) as usize]
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref CHECK_IN_REWARD_LIST: Vec<CheckInReward> = vec![
+        CheckInReward::new(1, REWARD_GOLD, 100, 1),
+        CheckInReward::new(2, REWARD_PROP, Carrot.0, 10),
+        CheckInReward::new(3, REWARD_GOLD, 200, 1),
+        CheckInReward::new(4, REWARD_PROP, Cabbage.0, 2),
+        CheckInReward::new(5, REWARD_GOLD, 300, 1),
+        CheckInReward::new(6, REWARD_PROP, Healing_Potion.0, 1),
+        CheckInReward::new(7, REWARD_PROP, Golden_Clover.0, 1),
+    ];
+}
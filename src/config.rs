@@ -1,12 +1,25 @@
-use crate::elf::{Elf, ElfGradeRandom, StandElf};
+use crate::elf::{Elf, ElfGradeRandom, ElfPity, SellPriceRule, StandElf, DEFAULT_STAND_ELF_RANDOM};
+use crate::achievement::Achievement;
+use crate::buff::BuffProp;
+use crate::checkin::CheckInReward;
+use crate::food::Food;
 use serde::Serialize;
+use std::cell::RefCell;
+use std::slice::IterMut;
 use zkwasm_rust_sdk::PoseidonHasher;
 use crate::prop::Prop;
-use crate::ranch::RanchSlot;
+use crate::quest::{Quest, DAILY_QUEST_COUNT};
+use crate::ranch::{RanchSlot, RanchTier, RanchUpkeep, RANCH_TIER_BASIC};
+use crate::season::Season;
+use crate::state::State;
+use crate::withdraw::WithdrawPolicy;
 
 pub const ENTITY_ATTRIBUTES_SIZE: usize = 4; //level speed efficiency productivity
 pub const LOCAL_ATTRIBUTES_SIZE: usize = 8;
 
+// 5秒一次tick，一天的 tick 数
+pub const TICKS_PER_DAY: u64 = 24 * 60 * 60 / 5;
+
 lazy_static::lazy_static! {
     pub static ref ADMIN_PUBKEY: [u64; 4] = {
         let bytes = include_bytes!("./admin.prikey");
@@ -22,8 +35,20 @@ pub struct Config {
     version: &'static str,
     elf_list: &'static Vec<StandElf>,
     rand_list: &'static Vec<ElfGradeRandom>,
+    pity: &'static ElfPity,
+    sell_price_rule: &'static SellPriceRule,
     store_list: &'static Vec<Prop>,
-    ranch_slot: &'static Vec<RanchSlot>,
+    ranch_slot: &'static Vec<RanchSlot>, // 基础等级的槽位，兼容只读取槽位列表的客户端
+    ranch_tiers: &'static Vec<RanchTier>,
+    ranch_upkeep: &'static RanchUpkeep,
+    food_list: &'static Vec<Food>,
+    buff_list: &'static Vec<BuffProp>,
+    ticks_per_day: u64,
+    withdraw_policy: &'static WithdrawPolicy,
+    achievement_list: &'static Vec<Achievement>,
+    quest_list: &'static Vec<Quest>,
+    daily_quest_count: u64,
+    check_in_rewards: &'static Vec<CheckInReward>,
 }
 
 /* bounty info
@@ -51,8 +76,20 @@ lazy_static::lazy_static! {
         version: "1.1",
         elf_list:&*Elf::get_all_elfs(),
         rand_list:&*Elf::get_all_randoms(),
+        pity: Elf::get_pity(),
+        sell_price_rule: Elf::get_sell_price_rule(),
         store_list:&*Prop::get_all_pops(),
-        ranch_slot: &*RanchSlot::get_all_ranch_slots(),
+        ranch_slot: RanchTier::get_basic_slots(),
+        ranch_tiers: RanchTier::get_all_tiers(),
+        ranch_upkeep: RanchUpkeep::get_upkeep(),
+        food_list: &*Food::get_all_foods(),
+        buff_list: &*BuffProp::get_all_buff_props(),
+        ticks_per_day: TICKS_PER_DAY,
+        withdraw_policy: WithdrawPolicy::get_policy(),
+        achievement_list: Achievement::get_all_achievements(),
+        quest_list: Quest::get_all_quests(),
+        daily_quest_count: DAILY_QUEST_COUNT,
+        check_in_rewards: CheckInReward::get_all_rewards(),
     };
 
 
@@ -72,8 +109,21 @@ impl Store {
 }
 
 impl Config {
+    // 编译的配置叠加管理员修改的数值，再加上当前生效的赛季活动
     pub fn to_json_string() -> String {
-        serde_json::to_string(&CONFIG.clone()).unwrap()
+        let mut config = serde_json::to_value(&CONFIG.clone()).unwrap();
+        config["elf_list"] = serde_json::to_value(&ConfigOverride::get_elf_list()).unwrap();
+        config["rand_list"] = serde_json::to_value(&ConfigOverride::get_rand_list()).unwrap();
+        config["store_list"] = serde_json::to_value(&ConfigOverride::get_prop_list()).unwrap();
+        let ranch_tiers = ConfigOverride::get_ranch_tier_list();
+        if let Some(basic) = ranch_tiers.iter().find(|t| t.tier == RANCH_TIER_BASIC) {
+            config["ranch_slot"] = serde_json::to_value(&basic.slots).unwrap();
+        }
+        config["ranch_tiers"] = serde_json::to_value(&ranch_tiers).unwrap();
+        let counter = State::get_counter();
+        config["season"] = serde_json::to_value(&Season::get_active(counter)).unwrap();
+        config["scheduled_seasons"] = serde_json::to_value(&Season::get_scheduled(counter)).unwrap();
+        serde_json::to_string(&config).unwrap()
     }
     pub fn autotick() -> bool {
         true
@@ -87,3 +137,172 @@ impl Config {
         0
     }
 }
+
+// 管理员可以修改的配置项
+pub const CONFIG_ELF_PRICE: u64 = 1; // 精灵买入价格，key 为精灵类型
+pub const CONFIG_PROP_PRICE: u64 = 2; // 道具价格，key 为道具类型
+pub const CONFIG_SLOT_PRICE: u64 = 3; // 槽位价格，key 为 牧场等级 << 32 | 槽位 id
+pub const CONFIG_GRADE_ODDS: u64 = 4; // 品质概率区间，整张表一起修改，key 为 0，value 见 get_grade_ends
+
+// 抽卡随机数的范围为 [1, GRADE_RANDOM_MAX]
+pub const GRADE_RANDOM_MAX: u64 = 100;
+
+// 管理员修改的配置，没有修改的项使用编译的配置
+// 保存在 State 的配置 key 中，初始化时加载
+#[derive(Clone, Debug, Serialize)]
+pub struct ConfigEntry {
+    pub table: u64,
+    pub key: u64,
+    pub value: [u64; 2],
+}
+
+pub struct ConfigOverride(RefCell<Vec<ConfigEntry>>);
+unsafe impl Sync for ConfigOverride {}
+
+lazy_static::lazy_static! {
+    pub static ref CONFIG_OVERRIDE: ConfigOverride = ConfigOverride(RefCell::new(vec![]));
+}
+
+impl ConfigOverride {
+    // 修改配置项，key 必须是编译配置中存在的项
+    pub fn set(table: u64, key: u64, value: [u64; 2]) -> bool {
+        let valid = match table {
+            CONFIG_ELF_PRICE => Elf::get_all_elfs().iter().any(|e| e.elf_type == key),
+            CONFIG_PROP_PRICE => Prop::get_prop_by_type(key).is_some(),
+            CONFIG_SLOT_PRICE => RanchTier::get_tier(key >> 32)
+                .map_or(false, |t| t.slots.iter().any(|s| s.id == key & 0xffff_ffff)),
+            CONFIG_GRADE_ODDS => key == 0 && ConfigOverride::get_grade_ends(value).is_some(),
+            _ => false,
+        };
+        if !valid {
+            return false;
+        }
+        let mut entries = CONFIG_OVERRIDE.0.borrow_mut();
+        entries.retain(|e| !(e.table == table && e.key == key));
+        entries.push(ConfigEntry { table, key, value });
+        true
+    }
+
+    pub fn get(table: u64, key: u64) -> Option<[u64; 2]> {
+        CONFIG_OVERRIDE
+            .0
+            .borrow()
+            .iter()
+            .find(|e| e.table == table && e.key == key)
+            .map(|e| e.value)
+    }
+
+    // 精灵买入价格
+    pub fn get_elf_price(elf_type: u64, default: u64) -> u64 {
+        ConfigOverride::get(CONFIG_ELF_PRICE, elf_type).map_or(default, |v| v[0])
+    }
+
+    // 道具价格
+    pub fn get_prop_price(prop_type: u64, default: u64) -> u64 {
+        ConfigOverride::get(CONFIG_PROP_PRICE, prop_type).map_or(default, |v| v[0])
+    }
+
+    // 牧场等级中的槽位价格
+    pub fn get_slot_price(tier: u64, slot_id: u64, default: u64) -> u64 {
+        ConfigOverride::get(CONFIG_SLOT_PRICE, tier << 32 | slot_id).map_or(default, |v| v[0])
+    }
+
+    // 解析品质概率表，按 DEFAULT_STAND_ELF_RANDOM 的顺序给出每个品质区间的结束值
+    // 每个结束值占 16 bit，前 4 个品质在 value[0]，之后的在 value[1]，低位在前
+    // 每个品质从上一个品质的结束值 + 1 开始，所以区间不会重叠也不会有空隙；
+    // 结束值不能减小（和上一个相同表示这个品质的概率为 0），最后一个必须是 GRADE_RANDOM_MAX，
+    // 保证 [1, GRADE_RANDOM_MAX] 的每个随机数都正好落在一个品质中。不满足时返回 None
+    pub fn get_grade_ends(value: [u64; 2]) -> Option<Vec<u64>> {
+        let count = DEFAULT_STAND_ELF_RANDOM.len();
+        if count > 8 {
+            return None;
+        }
+        let ends: Vec<u64> = (0..count)
+            .map(|i| (value[i / 4] >> ((i % 4) * 16)) & 0xffff)
+            .collect();
+        let increasing = ends.windows(2).all(|w| w[0] <= w[1]);
+        if !increasing || ends.last() != Some(&GRADE_RANDOM_MAX) {
+            return None;
+        }
+        Some(ends)
+    }
+
+    // 品质概率区间
+    pub fn get_rand_list() -> Vec<ElfGradeRandom> {
+        let ends = match ConfigOverride::get(CONFIG_GRADE_ODDS, 0).and_then(ConfigOverride::get_grade_ends) {
+            Some(ends) => ends,
+            None => return DEFAULT_STAND_ELF_RANDOM.clone(),
+        };
+        let mut start = 1;
+        DEFAULT_STAND_ELF_RANDOM
+            .iter()
+            .zip(ends)
+            .map(|(r, end)| {
+                let mut r = r.clone();
+                r.start = start;
+                r.end = end;
+                start = end + 1;
+                r
+            })
+            .collect()
+    }
+
+    pub fn get_elf_list() -> Vec<StandElf> {
+        Elf::get_all_elfs()
+            .iter()
+            .map(|e| {
+                let mut e = e.clone();
+                e.buy_price = ConfigOverride::get_elf_price(e.elf_type, e.buy_price);
+                e
+            })
+            .collect()
+    }
+
+    pub fn get_prop_list() -> Vec<Prop> {
+        Prop::get_all_pops()
+            .iter()
+            .map(|p| {
+                let mut p = p.clone();
+                p.price = ConfigOverride::get_prop_price(p.prop_type, p.price);
+                p
+            })
+            .collect()
+    }
+
+    pub fn get_ranch_tier_list() -> Vec<RanchTier> {
+        RanchTier::get_all_tiers()
+            .iter()
+            .map(|t| {
+                let slots = t
+                    .slots
+                    .iter()
+                    .map(|s| RanchSlot::new(s.id, ConfigOverride::get_slot_price(t.tier, s.id, s.price)))
+                    .collect();
+                RanchTier::new(t.tier, t.upgrade_price, t.max_slot, slots)
+            })
+            .collect()
+    }
+
+    pub fn to_data(data: &mut Vec<u64>) {
+        let entries = CONFIG_OVERRIDE.0.borrow();
+        data.push(entries.len() as u64);
+        for entry in entries.iter() {
+            data.push(entry.table);
+            data.push(entry.key);
+            data.push(entry.value[0]);
+            data.push(entry.value[1]);
+        }
+    }
+
+    pub fn from_data(u64data: &mut IterMut<u64>) {
+        let count = u64data.next().map_or(0, |v| *v);
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let table = *u64data.next().unwrap();
+            let key = *u64data.next().unwrap();
+            let value = [*u64data.next().unwrap(), *u64data.next().unwrap()];
+            entries.push(ConfigEntry { table, key, value });
+        }
+        *CONFIG_OVERRIDE.0.borrow_mut() = entries;
+    }
+}
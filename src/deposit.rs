@@ -0,0 +1,125 @@
+use serde::Serialize;
+use zkwasm_rest_abi::MERKLE_MAP;
+use zkwasm_rust_sdk::PoseidonHasher;
+
+// 已处理的 L1 充值记录 key 前缀，防止重复入账
+const DEPOSIT_KEY_TAG: u64 = 0xd0;
+// 充值记录索引的 key 前缀，按处理顺序保存充值编号，用于对账
+const DEPOSIT_INDEX_KEY_TAG: u64 = 0xd1;
+// 已处理充值数量的 key
+const DEPOSIT_COUNT_KEY: [u64; 4] = [DEPOSIT_INDEX_KEY_TAG, 0, 0, 0];
+
+// 充值类型
+pub const DEPOSIT_KIND_PROP: u64 = 1; // 充值道具
+pub const DEPOSIT_KIND_GOLD: u64 = 2; // 充值金币
+
+// 已处理的充值记录
+#[derive(Clone, Debug, Serialize)]
+pub struct DepositRecord {
+    pub tx_hash: [u64; 4], // L1 充值交易哈希，按大端拆成 4 个 u64，tx_hash[0] 是最高位
+    pub kind: u64,         // 充值类型
+    pub pid: [u64; 2],     // 入账玩家
+    pub value: u64,        // 金币数量或道具类型
+    pub count: u64,        // 道具数量，充值金币时为 1
+    pub counter: u64,      // 入账时事件队列的 counter
+}
+
+impl DepositRecord {
+    pub fn new(tx_hash: [u64; 4], kind: u64, pid: [u64; 2], value: u64, count: u64, counter: u64) -> Self {
+        DepositRecord {
+            tx_hash,
+            kind,
+            pid,
+            value,
+            count,
+            counter,
+        }
+    }
+
+    // key 只有 3 个位置可以放哈希，把完整的交易哈希压缩成 3 个 u64
+    // 记录中保存完整的交易哈希，读取时再比对一次
+    fn to_key(tx_hash: &[u64; 4]) -> [u64; 4] {
+        let mut hasher = PoseidonHasher::new();
+        for h in tx_hash {
+            hasher.update(*h);
+        }
+        let hash = hasher.finalize();
+        [DEPOSIT_KEY_TAG, hash[0], hash[1], hash[2]]
+    }
+
+    fn to_index_key(index: u64) -> [u64; 4] {
+        [DEPOSIT_INDEX_KEY_TAG, 1, index, 0]
+    }
+
+    // 充值是否已经处理过
+    pub fn is_processed(tx_hash: &[u64; 4]) -> bool {
+        DepositRecord::get_record(tx_hash).is_some()
+    }
+
+    // 保存充值记录，并追加到索引
+    pub fn store(&self) {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        kvpair.set(
+            &DepositRecord::to_key(&self.tx_hash),
+            &[
+                self.tx_hash[0],
+                self.tx_hash[1],
+                self.tx_hash[2],
+                self.tx_hash[3],
+                self.kind,
+                self.pid[0],
+                self.pid[1],
+                self.value,
+                self.count,
+                self.counter,
+            ],
+        );
+        let count = DepositRecord::get_count();
+        kvpair.set(&DepositRecord::to_index_key(count), &self.tx_hash);
+        kvpair.set(&DEPOSIT_COUNT_KEY, &[count + 1]);
+    }
+
+    // 根据交易哈希获取记录
+    pub fn get_record(tx_hash: &[u64; 4]) -> Option<DepositRecord> {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let data = kvpair.get(&DepositRecord::to_key(tx_hash));
+        if data.len() < 10 || data[0..4] != tx_hash[..] {
+            return None;
+        }
+        Some(DepositRecord::new(
+            *tx_hash,
+            data[4],
+            [data[5], data[6]],
+            data[7],
+            data[8],
+            data[9],
+        ))
+    }
+
+    // 已处理的充值数量
+    pub fn get_count() -> u64 {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let data = kvpair.get(&DEPOSIT_COUNT_KEY);
+        if data.is_empty() {
+            0
+        } else {
+            data[0]
+        }
+    }
+
+    // 按处理顺序获取充值记录
+    pub fn get_records(start: u64, limit: u64) -> Vec<DepositRecord> {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let end = DepositRecord::get_count().min(start.saturating_add(limit));
+        let mut records = vec![];
+        for index in start..end {
+            let data = kvpair.get(&DepositRecord::to_index_key(index));
+            if let Ok(tx_hash) = <[u64; 4]>::try_from(data.as_slice()) {
+                if let Some(record) = DepositRecord::get_record(&tx_hash) {
+                    records.push(record);
+                }
+            }
+        }
+        records
+    }
+}
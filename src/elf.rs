@@ -1,4 +1,4 @@
-use crate::config::get_random;
+use crate::config::{get_random, ConfigOverride};
 use crate::player::ElfPlayer;
 use lazy_static::lazy_static;
 use serde::Serialize;
@@ -6,7 +6,9 @@ use std::slice::IterMut;
 use std::str;
 use zkwasm_rest_abi::StorageData;
 use crate::error::ERROR_INVALID_PURCHASE_CONDITION;
-use crate::prop::{Cabbage, Carrot, Healing_Potion};
+use crate::food::Food;
+use crate::prop::Healing_Potion;
+use crate::season::Season;
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Elf {
@@ -24,16 +26,16 @@ pub struct Elf {
 }
 
 // 精灵类型，买入价格，卖出价格
-const Hippo: (u64, u64, u64) = (1, 100, 100);
-const Slerf: (u64, u64, u64) = (2, 300, 300);
-const Goat: (u64, u64, u64) = (3, 1800, 1800);
-const Pnut: (u64, u64, u64) = (4, 6000, 6000);
-const Popcat: (u64, u64, u64) = (5, 15000, 15000);
-const Brett: (u64, u64, u64) = (6, 37500, 37500);
-const Wif: (u64, u64, u64) = (7, 93750, 93750);
-const Bonk: (u64, u64, u64) = (8, 152000, 152000);
-const Pepe: (u64, u64, u64) = (9, 220000, 220000);
-const Doge: (u64, u64, u64) = (10, 300000, 300000);
+pub const Hippo: (u64, u64, u64) = (1, 100, 100);
+pub const Slerf: (u64, u64, u64) = (2, 300, 300);
+pub const Goat: (u64, u64, u64) = (3, 1800, 1800);
+pub const Pnut: (u64, u64, u64) = (4, 6000, 6000);
+pub const Popcat: (u64, u64, u64) = (5, 15000, 15000);
+pub const Brett: (u64, u64, u64) = (6, 37500, 37500);
+pub const Wif: (u64, u64, u64) = (7, 93750, 93750);
+pub const Bonk: (u64, u64, u64) = (8, 152000, 152000);
+pub const Pepe: (u64, u64, u64) = (9, 220000, 220000);
+pub const Doge: (u64, u64, u64) = (10, 300000, 300000);
 
 impl Elf {
     pub fn new(
@@ -60,11 +62,15 @@ impl Elf {
         }
     }
 
-    // 获取精灵
-    pub(crate) fn get_elf(rand: u64, elf_type: u64, elf_id: u64) -> Elf {
+    // 获取精灵，pity_count 为该类型连续未达到保底等级的购买次数，counter 用于判断赛季活动
+    pub(crate) fn get_elf(rand: u64, elf_type: u64, elf_id: u64, pity_count: u64, counter: u64) -> Elf {
         // 获取随机数，得到精灵品质区间获得等级
         let random = get_random(rand, 100);
-        let grade = Elf::get_grade_by_random(random);
+        let mut grade = Elf::get_grade_by_random(random, counter);
+        // 保底：连续 threshold 次没有达到保底等级，本次直接给保底等级
+        if grade < ELF_PITY.min_grade && pity_count + 1 >= ELF_PITY.threshold {
+            grade = ELF_PITY.min_grade;
+        }
         let elf_new_id = elf_id + 1;
         Elf::get_elf_by_type_and_grade(elf_type, grade, elf_new_id)
     }
@@ -72,7 +78,7 @@ impl Elf {
     // 根据类型判断是否可以购买精灵
     pub fn check_can_buy_elf(pid: &[u64; 2], ranch_id: u64, elf_type: u64) -> Result<u64, u32> {
         let player = ElfPlayer::get_from_pid(pid).unwrap();
-        match elf_type {
+        let price = match elf_type {
             x if x == Hippo.0 => Ok(100),
             x if x == Slerf.0 => Elf::check_can_buy_slerf(player, ranch_id),
             x if x == Goat.0 => Elf::check_can_buy_goat(player, ranch_id),
@@ -84,7 +90,9 @@ impl Elf {
             x if x == Pepe.0 => Elf::check_can_buy_pepe(player, ranch_id),
             x if x == Doge.0 => Elf::check_can_buy_doge(player, ranch_id),
             _ => Err(ERROR_INVALID_PURCHASE_CONDITION),
-        }
+        };
+        // 管理员修改过价格时使用修改后的价格
+        price.map(|price| ConfigOverride::get_elf_price(elf_type, price))
     }
 
     // 获取所有精灵信息
@@ -97,6 +105,16 @@ impl Elf {
         &*DEFAULT_STAND_ELF_RANDOM
     }
 
+    // 获取保底参数
+    pub fn get_pity() -> &'static ElfPity {
+        &*ELF_PITY
+    }
+
+    // 获取出售价格规则
+    pub fn get_sell_price_rule() -> &'static SellPriceRule {
+        &*SELL_PRICE_RULE
+    }
+
     // 是否可以购买 Slerf
     pub fn check_can_buy_slerf(mut player: ElfPlayer, ranch_id: u64) -> Result<u64, u32> {
         // 养一只成年Hippo
@@ -253,9 +271,11 @@ impl Elf {
     }
 
     // 获取等级
-    fn get_grade_by_random(random_num: u64) -> u64 {
+    fn get_grade_by_random(random_num: u64, counter: u64) -> u64 {
+        // 赛季活动提高随机数，最高不超过 100
+        let random_num = (random_num + Season::get_grade_bonus(counter)).min(100);
         // 遍历每个精灵等级区间
-        for grade_range in &*DEFAULT_STAND_ELF_RANDOM {
+        for grade_range in &ConfigOverride::get_rand_list() {
             // 判断随机数是否在当前区间内
             if random_num >= grade_range.start && random_num <= grade_range.end {
                 return grade_range.grade; // 如果在区间内，返回等级
@@ -264,12 +284,12 @@ impl Elf {
         1
     }
 
-    // 获取需要增加的经验值
-    pub fn compute_need_exp(growth_time: u64, exp: u64) -> u64 {
+    // 获取需要增加的经验值，growth_modifier 为成长增益系数（以 100 为 1.0 的基准）
+    pub fn compute_need_exp(growth_time: u64, exp: u64, growth_modifier: u64) -> u64 {
         let left_need_exp = 10000 - exp;
         // 因为growth_time 是分钟，但是这里分钟*了10，所以这里秒钟需要只需要*6
         // 5秒一次tick，所以每秒钟的经验需要*5
-        let need_exp = (10000 / (growth_time * 6)) * 5;
+        let need_exp = (10000 / (growth_time * 6)) * 5 * growth_modifier / 100;
         // 如果计算出的每次需要的经验值超过剩余经验值，返回剩余经验值
         if need_exp > left_need_exp {
             return left_need_exp;
@@ -335,8 +355,16 @@ impl Elf {
         tick_reduce
     }
 
-    // 计算需要增加的金币值
-    pub fn compute_need_gold(elf: Elf) -> u64 {
+    // 计算精灵的出售价格，按买入价格、品质、成长和健康折算
+    // 买入价格可以由管理员修改，价格超出 u64 时返回 None
+    pub fn compute_sell_price(elf: &Elf) -> Option<u64> {
+        let buy_price = StandElf::get_elf_by_type(elf.elf_type, elf.grade).buy_price;
+        let buy_price = ConfigOverride::get_elf_price(elf.elf_type, buy_price);
+        SELL_PRICE_RULE.compute(buy_price, elf.grade, elf.exp, elf.health)
+    }
+
+    // 计算需要增加的金币值，gold_modifier 为金币增益系数（以 100 为 1.0 的基准）
+    pub fn compute_need_gold(elf: Elf, gold_modifier: u64, upkeep_factor: u64, counter: u64) -> u64 {
         let left_can_add_gold = elf.max_gold_store - elf.current_gold_store;
 
         // 基础金币系数（保留整数计算，100 表示 1.0）
@@ -383,8 +411,17 @@ impl Elf {
             * satiety_factor
             / (100 * 100 * 100 * 100); // 每个系数按 100 倍缩放
 
-        // 每分钟 1 次产出
-        let need_add_per_tick = need_add ;
+        // 每分钟 1 次产出，叠加增益和赛季活动，牧场维护费未支付时产出减少
+        // 溢出时按剩余可存储金币产出
+        let season_modifier = Season::get_gold_modifier(counter);
+        let need_add_per_tick = match need_add
+            .checked_mul(gold_modifier)
+            .and_then(|v| (v / 100).checked_mul(season_modifier))
+            .and_then(|v| (v / 100).checked_mul(upkeep_factor))
+        {
+            Some(v) => v / 100,
+            None => return left_can_add_gold,
+        };
 
         // 检查是否超过剩余可存储金币
         if need_add_per_tick > left_can_add_gold {
@@ -395,29 +432,17 @@ impl Elf {
     }
 
     // 计算需要增加的饱食度
-    pub fn compute_need_add_satiety(prop_type: u64, elf: Elf) -> u64 {
-        zkwasm_rust_sdk::dbg!("prop_type is {:?}\n", prop_type);
-        let carrot = Carrot.0;
-        let cabbage = Cabbage.0;
-        let mut base_satiety: u64 = 0;
-
-
-        if prop_type == carrot {
-            base_satiety = 2;
-        } else if prop_type == cabbage {
-            base_satiety = 30;
-        }
-        zkwasm_rust_sdk::dbg!("base_satiety is {:?}\n", base_satiety);
-        if base_satiety == 0 {
-            return 0;
-        }
-
-        zkwasm_rust_sdk::dbg!("base_satiety1 is {:?}\n", base_satiety);
+    pub fn compute_need_add_satiety(food: &Food, elf: Elf) -> u64 {
+        zkwasm_rust_sdk::dbg!("prop_type is {:?}\n", food.prop_type);
+        let base_satiety = food.satiety;
+        // 精灵偏好系数（以 100 为 1.0 的基准）
+        let affinity = food.get_affinity(&elf);
+        zkwasm_rust_sdk::dbg!("base_satiety is {:?} affinity is {:?}\n", base_satiety, affinity);
 
         // 剩余饱食度
         let can_add_satiety = 10000 - elf.satiety;
         zkwasm_rust_sdk::dbg!("can_add_satiety is {:?}\n", can_add_satiety);
-        let prop_add_satiety = (base_satiety * 10000) / 100;
+        let prop_add_satiety = (base_satiety * 10000 * affinity) / (100 * 100);
         zkwasm_rust_sdk::dbg!("prop_add_satiety is {:?}\n", prop_add_satiety);
         if prop_add_satiety > can_add_satiety {
             return can_add_satiety;
@@ -425,6 +450,29 @@ impl Elf {
         prop_add_satiety
     }
 
+    // 计算食物额外增加的经验值
+    pub fn compute_food_add_exp(food: &Food, elf: Elf) -> u64 {
+        let left_need_exp = 10000 - elf.exp;
+        if food.exp_bonus > left_need_exp {
+            return left_need_exp;
+        }
+        food.exp_bonus
+    }
+
+    // 计算食物额外增加的金币
+    pub fn compute_food_add_gold(food: &Food, elf: Elf) -> u64 {
+        let left_can_add_gold = elf.max_gold_store - elf.current_gold_store;
+        if food.gold_bonus > left_can_add_gold {
+            return left_can_add_gold;
+        }
+        food.gold_bonus
+    }
+
+    // 是否是可以治疗精灵的道具
+    pub fn is_healing_prop(prop_type: u64) -> bool {
+        prop_type == Healing_Potion.0
+    }
+
     // 计算需要增加的健康值
     pub fn compute_need_add_health(prop_type: u64, elf: Elf) -> u64 {
         let healing_potion = Healing_Potion.0;
@@ -580,6 +628,89 @@ impl ElfGradeRandom {
     }
 }
 
+// 精灵品质保底参数
+#[derive(Clone, Debug, Serialize)]
+pub struct ElfPity {
+    pub threshold: u64, // 连续多少次未达到保底等级后触发保底
+    pub min_grade: u64, // 保底等级
+}
+
+// 精灵出售价格规则，以 100 为 1.0
+// 出售价格 = 买入价格 * 基础比例 * 品质系数 * 成长系数 * 健康系数
+#[derive(Clone, Debug, Serialize)]
+pub struct SellPriceRule {
+    pub base_rate: u64,          // 基础比例，和买入价格的差价
+    pub grade_factors: Vec<u64>, // 品质 1~5 的系数
+    pub baby_factor: u64,        // 经验值为 0 时的成长系数
+    pub adult_factor: u64,       // 成年时的成长系数
+    pub min_health_factor: u64,  // 最低健康系数
+}
+
+impl SellPriceRule {
+    // 系数相乘最大约 2.25e8，用 u128 计算避免买入价格较大时溢出
+    pub fn compute(&self, buy_price: u64, grade: u64, exp: u64, health: u64) -> Option<u64> {
+        // 品质系数（以 100 为 1.0 的基准）
+        let grade_factor = self
+            .grade_factors
+            .get((grade as usize).saturating_sub(1))
+            .copied()
+            .unwrap_or(100);
+
+        // 成长系数，按经验值从幼年系数线性增长到成年系数
+        let exp = exp.min(10000);
+        let growth_factor = self.baby_factor + (self.adult_factor - self.baby_factor) * exp / 10000;
+
+        // 健康系数，健康度 10000 为 1.0，不低于最低系数
+        let health_factor = (health / 100).max(self.min_health_factor);
+
+        let price = buy_price as u128
+            * self.base_rate as u128
+            * grade_factor as u128
+            * growth_factor as u128
+            * health_factor as u128
+            / (100 * 100 * 100 * 100);
+        u64::try_from(price).ok()
+    }
+}
+
+// 被管理员移除的精灵，保留数据用于恢复
+#[derive(Clone, Debug, Serialize)]
+pub struct RemovedElf {
+    pub ranch_id: u64, // 原来所在的牧场
+    pub elf: Elf,
+}
+
+impl StorageData for RemovedElf {
+    fn from_data(u64data: &mut IterMut<u64>) -> Self {
+        let ranch_id = *u64data.next().unwrap();
+        let elf = Elf::from_data(u64data);
+        RemovedElf { ranch_id, elf }
+    }
+    fn to_data(&self, data: &mut Vec<u64>) {
+        data.push(self.ranch_id);
+        self.elf.to_data(data);
+    }
+}
+
+// 玩家每种精灵的保底计数
+#[derive(Clone, Debug, Serialize)]
+pub struct ElfPityCount {
+    pub elf_type: u64, // 精灵类型
+    pub count: u64,    // 连续未达到保底等级的次数
+}
+
+impl StorageData for ElfPityCount {
+    fn from_data(u64data: &mut IterMut<u64>) -> Self {
+        let elf_type = *u64data.next().unwrap();
+        let count = *u64data.next().unwrap();
+        ElfPityCount { elf_type, count }
+    }
+    fn to_data(&self, data: &mut Vec<u64>) {
+        data.push(self.elf_type);
+        data.push(self.count);
+    }
+}
+
 lazy_static::lazy_static! {
     pub static ref DEFAULT_STAND_ELF: Vec<StandElf> = vec![
         // Hippo
@@ -660,4 +791,90 @@ lazy_static::lazy_static! {
         ElfGradeRandom::new(5, 99, 100),
     ];
 
+    pub static ref ELF_PITY: ElfPity = ElfPity {
+        threshold: 30,
+        min_grade: 4,
+    };
+
+    pub static ref SELL_PRICE_RULE: SellPriceRule = SellPriceRule {
+        base_rate: 60,
+        grade_factors: vec![100, 120, 150, 200, 250],
+        baby_factor: 50,
+        adult_factor: 150,
+        min_health_factor: 20,
+    };
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grade_by_random_follows_default_odds() {
+        assert_eq!(Elf::get_grade_by_random(1, 0), 1);
+        assert_eq!(Elf::get_grade_by_random(50, 0), 1);
+        assert_eq!(Elf::get_grade_by_random(51, 0), 2);
+        assert_eq!(Elf::get_grade_by_random(90, 0), 3);
+        assert_eq!(Elf::get_grade_by_random(98, 0), 4);
+        assert_eq!(Elf::get_grade_by_random(100, 0), 5);
+    }
+
+    #[test]
+    fn pity_not_reached_keeps_random_grade() {
+        let pity = Elf::get_pity();
+        for rand in [0, 7, 12345] {
+            let expected = Elf::get_grade_by_random(get_random(rand, 100), 0);
+            let elf = Elf::get_elf(rand, Goat.0, 0, pity.threshold - 2, 0);
+            assert_eq!(elf.grade, expected);
+            assert_eq!(elf.id, 1);
+        }
+    }
+
+    #[test]
+    fn pity_threshold_guarantees_min_grade() {
+        let pity = Elf::get_pity();
+        for rand in [0, 7, 12345] {
+            let elf = Elf::get_elf(rand, Goat.0, 0, pity.threshold - 1, 0);
+            assert!(elf.grade >= pity.min_grade);
+            assert_eq!(elf.elf_type, Goat.0);
+        }
+    }
+
+    fn goat(grade: u64, exp: u64, health: u64) -> Elf {
+        let mut elf = Elf::get_elf_by_type_and_grade(Goat.0, grade, 1);
+        elf.exp = exp;
+        elf.health = health;
+        elf
+    }
+
+    #[test]
+    fn sell_price_by_grade_and_growth() {
+        // 幼年满健康：1800 * 0.6 * 1.0 * 0.5
+        assert_eq!(Elf::compute_sell_price(&goat(1, 0, 10000)), Some(540));
+        // 半成长：成长系数 1.0
+        assert_eq!(Elf::compute_sell_price(&goat(1, 5000, 10000)), Some(1080));
+        // 五星成年：1800 * 0.6 * 2.5 * 1.5
+        assert_eq!(Elf::compute_sell_price(&goat(5, 10000, 10000)), Some(4050));
+    }
+
+    #[test]
+    fn sell_price_health_floor() {
+        // 健康度为 0 时按最低健康系数 0.2 计算
+        assert_eq!(Elf::compute_sell_price(&goat(1, 10000, 0)), Some(324));
+        assert_eq!(
+            Elf::compute_sell_price(&goat(1, 10000, 1000)),
+            Elf::compute_sell_price(&goat(1, 10000, 0))
+        );
+    }
+
+    #[test]
+    fn sell_price_large_buy_price() {
+        let rule = Elf::get_sell_price_rule();
+        // 之前用 u64 连乘会溢出的价格
+        assert_eq!(rule.compute(100_000_000_000, 5, 10000, 10000), Some(225_000_000_000));
+        assert_eq!(rule.compute(u64::MAX / 2, 1, 0, 10000), Some(((u64::MAX / 2) as u128 * 3 / 10) as u64));
+        assert_eq!(rule.compute(u64::MAX, 5, 10000, 10000), None);
+    }
 }
@@ -18,4 +18,74 @@ pub const  ERROR_THIS_PROP_MUST_BE_USED_USDT: u32 = 11;
 
 pub const  ERROR_MAX_ELF_SLOT: u32 = 12;
 
-pub const  ERROR_MUST_ADMIN_KEY:u32 =13;
\ No newline at end of file
+pub const  ERROR_MUST_ADMIN_KEY:u32 =13;
+
+pub const  ERROR_PROP_NOT_FOOD:u32 =14;
+
+pub const  ERROR_PROP_NOT_PLACEABLE:u32 =15;
+
+pub const  ERROR_INVALID_BUY_COUNT:u32 =16;
+
+pub const  ERROR_PROP_NOT_BUFF:u32 =17;
+
+pub const  ERROR_WITHDRAW_TOO_SMALL:u32 =18;
+
+pub const  ERROR_GOLD_NOT_WITHDRAWABLE:u32 =19;
+
+pub const  ERROR_WITHDRAW_DAILY_LIMIT:u32 =20;
+
+pub const  ERROR_DEPOSIT_ALREADY_PROCESSED:u32 =21;
+
+pub const  ERROR_DEPOSIT_AMOUNT_MISMATCH:u32 =22;
+
+pub const  ERROR_ACHIEVEMENT_NOT_FOUND:u32 =23;
+
+pub const  ERROR_ACHIEVEMENT_LOCKED:u32 =24;
+
+pub const  ERROR_ACHIEVEMENT_CLAIMED:u32 =25;
+
+pub const  ERROR_QUEST_NOT_FOUND:u32 =26;
+
+pub const  ERROR_QUEST_NOT_COMPLETED:u32 =27;
+
+pub const  ERROR_QUEST_CLAIMED:u32 =28;
+
+pub const  ERROR_ALREADY_CHECKED_IN:u32 =29;
+
+pub const  ERROR_INVALID_SEASON:u32 =30;
+
+pub const  ERROR_INVALID_CONFIG:u32 =31;
+
+pub const  ERROR_PLAYER_FROZEN:u32 =32;
+
+pub const  ERROR_REMOVED_ELF_NOT_FOUND:u32 =33;
+
+pub const  ERROR_INVALID_ADMIN:u32 =34;
+
+pub const  ERROR_ADMIN_NOT_FOUND:u32 =35;
+
+pub const  ERROR_GAME_PAUSED:u32 =36;
+
+pub const  ERROR_WITHDRAW_PAUSED:u32 =37;
+
+pub const  ERROR_PURCHASE_PAUSED:u32 =38;
+
+pub const  ERROR_INVALID_PAUSE_FLAGS:u32 =39;
+
+pub const  ERROR_RANCH_TIER_NOT_FOUND:u32 =40;
+
+pub const  ERROR_RANCH_SLOT_NOT_FOUND:u32 =41;
+
+pub const  ERROR_INVALID_SEASON_MODIFIER:u32 =42;
+
+pub const  ERROR_SEASON_OVERLAP:u32 =43;
+
+pub const  ERROR_MAX_RANCH_TIER:u32 =44;
+
+pub const  ERROR_GOLD_OVERFLOW:u32 =45;
+
+pub const  ERROR_PROP_COUNT_OVERFLOW:u32 =46;
+
+pub const  ERROR_INVALID_DEPOSIT_AMOUNT:u32 =47;
+
+pub const  ERROR_PROP_NOT_HEALING:u32 =48;
\ No newline at end of file
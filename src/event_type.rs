@@ -5,4 +5,8 @@ pub const HEALTH_REDUCE: u64 = 3; // 健康减少
 pub const SATIETY_REDUCE: u64 = 4; // 饱食减少
 pub const ADD_SHIT: u64 = 5; // 产生大便
 
-pub const HEALTH_ADD: u64 = 6; // 健康增加
\ No newline at end of file
+pub const HEALTH_ADD: u64 = 6; // 健康增加
+
+pub const BUFF_EXPIRE: u64 = 7; // 增益过期
+
+pub const RANCH_UPKEEP: u64 = 8; // 牧场维护费
\ No newline at end of file
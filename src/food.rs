@@ -0,0 +1,89 @@
+use serde::Serialize;
+use crate::elf::{Bonk, Doge, Elf, Goat, Hippo, Pnut, Slerf};
+use crate::prop::{Cabbage, Carrot, Honey};
+
+// 食物偏好，bonus 以 100 为 1.0 的基准
+#[derive(Clone, Debug, Serialize)]
+pub struct FoodAffinity {
+    pub elf_type: u64, // 精灵类型
+    pub bonus: u64,    // 饱食度加成系数
+}
+
+impl FoodAffinity {
+    pub fn new(elf_type: u64, bonus: u64) -> Self {
+        FoodAffinity { elf_type, bonus }
+    }
+}
+
+// 食物参数
+#[derive(Clone, Debug, Serialize)]
+pub struct Food {
+    pub prop_type: u64,                // 道具类型
+    pub satiety: u64,                  // 基础饱食度 (百分比)
+    pub affinities: Vec<FoodAffinity>, // 精灵偏好
+    pub exp_bonus: u64,                // 额外增加的经验值
+    pub gold_bonus: u64,               // 额外增加的金币
+}
+
+impl Food {
+    pub fn new(
+        prop_type: u64,
+        satiety: u64,
+        affinities: Vec<FoodAffinity>,
+        exp_bonus: u64,
+        gold_bonus: u64,
+    ) -> Self {
+        Food {
+            prop_type,
+            satiety,
+            affinities,
+            exp_bonus,
+            gold_bonus,
+        }
+    }
+
+    pub fn get_all_foods() -> &'static Vec<Food> {
+        &*FOOD_LIST
+    }
+
+    // 根据道具类型返回食物，不是食物返回 None
+    pub fn get_food_by_type(prop_type: u64) -> Option<&'static Food> {
+        FOOD_LIST.iter().find(|f| f.prop_type == prop_type)
+    }
+
+    // 获取精灵对该食物的偏好系数
+    pub fn get_affinity(&self, elf: &Elf) -> u64 {
+        self.affinities
+            .iter()
+            .find(|a| a.elf_type == elf.elf_type)
+            .map_or(100, |a| a.bonus)
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref FOOD_LIST: Vec<Food> = {
+        vec![
+            Food::new(
+                Carrot.0,
+                2,
+                vec![FoodAffinity::new(Hippo.0, 150), FoodAffinity::new(Slerf.0, 150)],
+                0,
+                0,
+            ),
+            Food::new(
+                Cabbage.0,
+                30,
+                vec![FoodAffinity::new(Goat.0, 120), FoodAffinity::new(Pnut.0, 120)],
+                0,
+                0,
+            ),
+            Food::new(
+                Honey.0,
+                10,
+                vec![FoodAffinity::new(Bonk.0, 200), FoodAffinity::new(Doge.0, 200)],
+                100,
+                20,
+            ),
+        ]
+    };
+}
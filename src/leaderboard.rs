@@ -0,0 +1,257 @@
+use serde::Serialize;
+use zkwasm_rest_abi::MERKLE_MAP;
+
+// 排行榜的 key 前缀，[LEADERBOARD_KEY_TAG, 排行榜类型, 0, 0]
+const LEADERBOARD_KEY_TAG: u64 = 0xb0;
+// 每个排行榜展示的名次
+const LEADERBOARD_SIZE: usize = 20;
+// 精灵收藏榜保存的候选名次，榜上玩家分数下降跌出时从候选中补位
+const COLLECTION_CANDIDATE_SIZE: usize = 100;
+
+// 排行榜类型
+pub const LEADERBOARD_GOLD: u64 = 1; // 累计收集金币
+pub const LEADERBOARD_COLLECTION: u64 = 2; // 精灵收藏，最高品质 << 32 | 拥有最高品质精灵的数量
+pub const LEADERBOARD_ADULT: u64 = 3; // 累计养成年的精灵
+
+// 排行榜中的一名玩家
+#[derive(Clone, Debug, Serialize)]
+pub struct LeaderboardEntry {
+    pub pid: [u64; 2],
+    pub score: u64,
+}
+
+pub struct Leaderboard;
+
+impl Leaderboard {
+    fn to_key(board_type: u64) -> [u64; 4] {
+        [LEADERBOARD_KEY_TAG, board_type, 0, 0]
+    }
+
+    // 累计金币和成年数量只会增加，按历史最高分数排名；精灵收藏按玩家当前拥有的精灵排名，分数可以下降
+    fn is_monotonic(board_type: u64) -> bool {
+        board_type != LEADERBOARD_COLLECTION
+    }
+
+    // 保存的名次，可以下降的排行榜多保存一些候选
+    fn get_capacity(board_type: u64) -> usize {
+        if Leaderboard::is_monotonic(board_type) {
+            LEADERBOARD_SIZE
+        } else {
+            COLLECTION_CANDIDATE_SIZE
+        }
+    }
+
+    // 保存的所有名次，按分数从高到低
+    fn load(board_type: u64) -> Vec<LeaderboardEntry> {
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        let data = kvpair.get(&Leaderboard::to_key(board_type));
+        data.chunks_exact(3)
+            .map(|c| LeaderboardEntry {
+                pid: [c[0], c[1]],
+                score: c[2],
+            })
+            .collect()
+    }
+
+    // 获取排行榜展示的名次，按分数从高到低
+    pub fn get_entries(board_type: u64) -> Vec<LeaderboardEntry> {
+        let mut entries = Leaderboard::load(board_type);
+        entries.truncate(LEADERBOARD_SIZE);
+        entries
+    }
+
+    // 玩家分数变化后更新排行榜
+    // 只增不减的排行榜记录玩家达到过的最高分数，分数下降时不更新，榜单是所有玩家历史最高分数的前几名；
+    // 精灵收藏榜记录玩家当前的分数，分数下降时移动位置，降到 0 或低于所有候选时移出，由后面的候选补位。
+    // 候选之外的玩家分数没有记录，要等到下次分数变化时才会重新上榜
+    pub fn update(board_type: u64, pid: &[u64; 2], score: u64) {
+        let mut entries = Leaderboard::load(board_type);
+        let capacity = Leaderboard::get_capacity(board_type);
+        let changed = if Leaderboard::is_monotonic(board_type) {
+            Leaderboard::insert(&mut entries, pid, score, capacity)
+        } else {
+            Leaderboard::replace(&mut entries, pid, score, capacity)
+        };
+        if !changed {
+            return;
+        }
+
+        let mut data = Vec::with_capacity(entries.len() * 3);
+        for entry in &entries {
+            data.push(entry.pid[0]);
+            data.push(entry.pid[1]);
+            data.push(entry.score);
+        }
+        let kvpair = unsafe { &mut MERKLE_MAP };
+        kvpair.set(&Leaderboard::to_key(board_type), data.as_slice());
+    }
+
+    // 把玩家的新分数放入按分数从高到低排列的榜单，只保留玩家的最高分数，榜单变化时返回 true
+    // 分数相同时先上榜的排在前面
+    fn insert(entries: &mut Vec<LeaderboardEntry>, pid: &[u64; 2], score: u64, capacity: usize) -> bool {
+        if score == 0 {
+            return false;
+        }
+        if let Some(index) = entries.iter().position(|e| e.pid == *pid) {
+            if entries[index].score >= score {
+                return false;
+            }
+            entries.remove(index);
+        } else if entries.len() >= capacity && entries.last().map_or(false, |e| e.score >= score) {
+            return false;
+        }
+        Leaderboard::place(entries, pid, score, capacity);
+        true
+    }
+
+    // 用玩家的当前分数替换榜单中的分数，分数为 0 或进不了榜单时移出，榜单变化时返回 true
+    fn replace(entries: &mut Vec<LeaderboardEntry>, pid: &[u64; 2], score: u64, capacity: usize) -> bool {
+        let removed = match entries.iter().position(|e| e.pid == *pid) {
+            Some(index) => {
+                if entries[index].score == score {
+                    return false;
+                }
+                entries.remove(index);
+                true
+            }
+            None => false,
+        };
+        if score == 0 || (entries.len() >= capacity && entries.last().map_or(false, |e| e.score >= score)) {
+            return removed;
+        }
+        Leaderboard::place(entries, pid, score, capacity);
+        true
+    }
+
+    // 放在分数相同的玩家之后，超出名次的移出
+    fn place(entries: &mut Vec<LeaderboardEntry>, pid: &[u64; 2], score: u64, capacity: usize) {
+        let index = entries.iter().position(|e| e.score < score).unwrap_or(entries.len());
+        entries.insert(index, LeaderboardEntry { pid: *pid, score });
+        entries.truncate(capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(entries: &[LeaderboardEntry]) -> Vec<(u64, u64)> {
+        entries.iter().map(|e| (e.pid[0], e.score)).collect()
+    }
+
+    fn full_board(size: usize) -> Vec<LeaderboardEntry> {
+        (0..size as u64)
+            .map(|i| LeaderboardEntry { pid: [i + 1, 0], score: 1000 - i * 10 })
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_descending_order() {
+        let mut entries = vec![];
+        assert!(Leaderboard::insert(&mut entries, &[1, 0], 50, LEADERBOARD_SIZE));
+        assert!(Leaderboard::insert(&mut entries, &[2, 0], 80, LEADERBOARD_SIZE));
+        assert!(Leaderboard::insert(&mut entries, &[3, 0], 60, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), vec![(2, 80), (3, 60), (1, 50)]);
+    }
+
+    #[test]
+    fn insert_tie_goes_after_existing() {
+        let mut entries = vec![];
+        assert!(Leaderboard::insert(&mut entries, &[1, 0], 50, LEADERBOARD_SIZE));
+        assert!(Leaderboard::insert(&mut entries, &[2, 0], 50, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), vec![(1, 50), (2, 50)]);
+    }
+
+    #[test]
+    fn insert_keeps_best_score() {
+        let mut entries = vec![];
+        assert!(Leaderboard::insert(&mut entries, &[1, 0], 50, LEADERBOARD_SIZE));
+        assert!(Leaderboard::insert(&mut entries, &[2, 0], 70, LEADERBOARD_SIZE));
+        // 分数降低不影响榜单
+        assert!(!Leaderboard::insert(&mut entries, &[2, 0], 40, LEADERBOARD_SIZE));
+        assert!(!Leaderboard::insert(&mut entries, &[2, 0], 70, LEADERBOARD_SIZE));
+        // 分数提高则移动位置，不重复上榜
+        assert!(Leaderboard::insert(&mut entries, &[1, 0], 90, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), vec![(1, 90), (2, 70)]);
+    }
+
+    #[test]
+    fn insert_rejects_zero_score() {
+        let mut entries = vec![];
+        assert!(!Leaderboard::insert(&mut entries, &[1, 0], 0, LEADERBOARD_SIZE));
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn insert_full_board_evicts_last() {
+        let mut entries = full_board(LEADERBOARD_SIZE);
+        let last = entries.last().unwrap().score;
+        assert!(Leaderboard::insert(&mut entries, &[100, 0], last + 1, LEADERBOARD_SIZE));
+        assert_eq!(entries.len(), LEADERBOARD_SIZE);
+        assert_eq!(scores(&entries).last(), Some(&(100, last + 1)));
+        assert!(!entries.iter().any(|e| e.pid == [LEADERBOARD_SIZE as u64, 0]));
+    }
+
+    #[test]
+    fn insert_full_board_rejects_not_above_last() {
+        let mut entries = full_board(LEADERBOARD_SIZE);
+        let last = entries.last().unwrap().score;
+        assert!(!Leaderboard::insert(&mut entries, &[100, 0], last, LEADERBOARD_SIZE));
+        assert!(!Leaderboard::insert(&mut entries, &[100, 0], last - 1, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), scores(&full_board(LEADERBOARD_SIZE)));
+    }
+
+    #[test]
+    fn replace_moves_down_on_lower_score() {
+        let mut entries = vec![];
+        assert!(Leaderboard::replace(&mut entries, &[1, 0], 90, LEADERBOARD_SIZE));
+        assert!(Leaderboard::replace(&mut entries, &[2, 0], 70, LEADERBOARD_SIZE));
+        assert!(Leaderboard::replace(&mut entries, &[3, 0], 50, LEADERBOARD_SIZE));
+        assert!(!Leaderboard::replace(&mut entries, &[1, 0], 90, LEADERBOARD_SIZE));
+        // 卖出精灵后分数下降，排到分数相同的玩家之后
+        assert!(Leaderboard::replace(&mut entries, &[1, 0], 50, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), vec![(2, 70), (3, 50), (1, 50)]);
+    }
+
+    #[test]
+    fn replace_zero_score_drops_out() {
+        let mut entries = vec![];
+        assert!(Leaderboard::replace(&mut entries, &[1, 0], 90, LEADERBOARD_SIZE));
+        assert!(Leaderboard::replace(&mut entries, &[2, 0], 70, LEADERBOARD_SIZE));
+        assert!(Leaderboard::replace(&mut entries, &[1, 0], 0, LEADERBOARD_SIZE));
+        assert_eq!(scores(&entries), vec![(2, 70)]);
+        assert!(!Leaderboard::replace(&mut entries, &[3, 0], 0, LEADERBOARD_SIZE));
+    }
+
+    #[test]
+    fn replace_candidates_refill_the_board() {
+        let mut entries = full_board(COLLECTION_CANDIDATE_SIZE);
+        let next = entries[LEADERBOARD_SIZE].pid;
+        // 榜首跌到候选最后，原来的第 LEADERBOARD_SIZE + 1 名补位进入展示的名次
+        let last = entries.last().unwrap().score;
+        assert!(Leaderboard::replace(&mut entries, &[1, 0], last, COLLECTION_CANDIDATE_SIZE));
+        assert_eq!(entries.len(), COLLECTION_CANDIDATE_SIZE);
+        assert_eq!(entries[LEADERBOARD_SIZE - 1].pid, next);
+        assert_eq!(entries.last().unwrap().pid, [1, 0]);
+    }
+
+    #[test]
+    fn replace_full_candidates_rejects_lower_score() {
+        let mut entries = full_board(COLLECTION_CANDIDATE_SIZE);
+        let last = entries.last().unwrap().score;
+        assert!(!Leaderboard::replace(&mut entries, &[200, 0], last, COLLECTION_CANDIDATE_SIZE));
+        assert_eq!(scores(&entries), scores(&full_board(COLLECTION_CANDIDATE_SIZE)));
+        // 分数高于最后一名时挤掉最后一名
+        assert!(Leaderboard::replace(&mut entries, &[200, 0], last + 1, COLLECTION_CANDIDATE_SIZE));
+        assert_eq!(entries.len(), COLLECTION_CANDIDATE_SIZE);
+        assert_eq!(entries.last().unwrap().pid, [200, 0]);
+    }
+
+    #[test]
+    fn collection_board_is_not_monotonic() {
+        assert!(Leaderboard::is_monotonic(LEADERBOARD_GOLD));
+        assert!(Leaderboard::is_monotonic(LEADERBOARD_ADULT));
+        assert!(!Leaderboard::is_monotonic(LEADERBOARD_COLLECTION));
+        assert_eq!(Leaderboard::get_capacity(LEADERBOARD_COLLECTION), COLLECTION_CANDIDATE_SIZE);
+    }
+}
@@ -7,9 +7,63 @@ pub mod error;
 pub mod events;
 pub mod player;
 pub mod state;
+mod activity;
+mod achievement;
+mod quest;
+mod checkin;
+mod leaderboard;
+mod pending;
+mod season;
+mod audit;
+mod admin;
 mod prop;
+mod food;
+mod buff;
+mod withdraw;
+mod deposit;
+mod supply;
 mod ranch;
 pub mod event_type;
 use crate::config::Config;
 use crate::state::{State, Transaction};
 zkwasm_rest_abi::create_zkwasm_apis!(Transaction, State, Config);
+
+#[wasm_bindgen]
+pub fn query_activities(pkey: Vec<u64>) -> String {
+    State::get_activities(pkey)
+}
+
+#[wasm_bindgen]
+pub fn query_pending_elfs(pkey: Vec<u64>) -> String {
+    State::get_pending_elfs(pkey)
+}
+
+#[wasm_bindgen]
+pub fn query_daily_quests() -> String {
+    State::get_daily_quests()
+}
+
+#[wasm_bindgen]
+pub fn query_leaderboard(board_type: u64) -> String {
+    State::get_leaderboard(board_type)
+}
+
+#[wasm_bindgen]
+pub fn query_admins() -> String {
+    State::get_admins()
+}
+
+#[wasm_bindgen]
+pub fn query_audit_records(start: u64, limit: u64) -> String {
+    State::get_audit_records(start, limit)
+}
+
+#[wasm_bindgen]
+pub fn query_deposit(tx_hash: Vec<u64>) -> String {
+    State::get_deposit(tx_hash)
+}
+
+#[wasm_bindgen]
+pub fn query_deposits(start: u64, limit: u64) -> String {
+    State::get_deposits(start, limit)
+}
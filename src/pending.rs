@@ -0,0 +1,114 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::slice::IterMut;
+
+// 已付款、等待开奖的精灵购买
+// 购买时还不知道下一次 tick 的签名，开奖用包含 tick 签名的种子，购买者无法预先算出品质
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingElf {
+    pub pid: [u64; 2],
+    pub ranch_id: u64,
+    pub elf_type: u64,
+    pub price: u64,   // 已支付的金币
+    pub counter: u64, // 购买时事件队列的 counter
+}
+
+// 所有玩家等待开奖的购买，按购买顺序，在下一次 tick 中全部开奖
+pub struct PendingElfList(RefCell<Vec<PendingElf>>);
+unsafe impl Sync for PendingElfList {}
+
+lazy_static::lazy_static! {
+    pub static ref PENDING_ELF_LIST: PendingElfList = PendingElfList(RefCell::new(vec![]));
+}
+
+impl PendingElf {
+    pub fn add(pending: PendingElf) {
+        PENDING_ELF_LIST.0.borrow_mut().push(pending);
+    }
+
+    // 玩家在牧场中等待开奖的数量，这些购买已经占用了槽位
+    pub fn count(pid: &[u64; 2], ranch_id: u64) -> u64 {
+        PENDING_ELF_LIST
+            .0
+            .borrow()
+            .iter()
+            .filter(|p| p.pid == *pid && p.ranch_id == ranch_id)
+            .count() as u64
+    }
+
+    pub fn get_by_pid(pid: &[u64; 2]) -> Vec<PendingElf> {
+        PENDING_ELF_LIST
+            .0
+            .borrow()
+            .iter()
+            .filter(|p| p.pid == *pid)
+            .cloned()
+            .collect()
+    }
+
+    // 取出所有等待开奖的购买
+    pub fn take_all() -> Vec<PendingElf> {
+        PENDING_ELF_LIST.0.borrow_mut().drain(..).collect()
+    }
+
+    pub fn to_data(data: &mut Vec<u64>) {
+        let list = PENDING_ELF_LIST.0.borrow();
+        data.push(list.len() as u64);
+        for p in list.iter() {
+            data.push(p.pid[0]);
+            data.push(p.pid[1]);
+            data.push(p.ranch_id);
+            data.push(p.elf_type);
+            data.push(p.price);
+            data.push(p.counter);
+        }
+    }
+
+    pub fn from_data(u64data: &mut IterMut<u64>) {
+        let count = u64data.next().map_or(0, |v| *v);
+        let mut list = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            list.push(PendingElf {
+                pid: [*u64data.next().unwrap(), *u64data.next().unwrap()],
+                ranch_id: *u64data.next().unwrap(),
+                elf_type: *u64data.next().unwrap(),
+                price: *u64data.next().unwrap(),
+                counter: *u64data.next().unwrap(),
+            });
+        }
+        *PENDING_ELF_LIST.0.borrow_mut() = list;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(pid: [u64; 2], ranch_id: u64) -> PendingElf {
+        PendingElf { pid, ranch_id, elf_type: 3, price: 1800, counter: 7 }
+    }
+
+    #[test]
+    fn pending_reserve_and_resolve() {
+        PendingElf::add(pending([1, 0], 1));
+        PendingElf::add(pending([1, 0], 1));
+        PendingElf::add(pending([1, 0], 2));
+        PendingElf::add(pending([2, 0], 1));
+        assert_eq!(PendingElf::count(&[1, 0], 1), 2);
+        assert_eq!(PendingElf::count(&[1, 0], 2), 1);
+        assert_eq!(PendingElf::count(&[3, 0], 1), 0);
+        assert_eq!(PendingElf::get_by_pid(&[1, 0]).len(), 3);
+
+        // 保存后重新加载，顺序不变
+        let mut data = vec![];
+        PendingElf::to_data(&mut data);
+        assert_eq!(data.len(), 1 + 4 * 6);
+        PendingElf::from_data(&mut data.iter_mut());
+
+        let all = PendingElf::take_all();
+        let order: Vec<([u64; 2], u64)> = all.iter().map(|p| (p.pid, p.ranch_id)).collect();
+        assert_eq!(order, vec![([1, 0], 1), ([1, 0], 1), ([1, 0], 2), ([2, 0], 1)]);
+        assert_eq!(PendingElf::count(&[1, 0], 1), 0);
+        assert!(PendingElf::take_all().is_empty());
+    }
+}
@@ -1,13 +1,42 @@
-use crate::elf::Elf;
-use crate::event_type::{ADD_EXP, ADD_GOLD, ADD_SHIT, HEALTH_ADD, HEALTH_REDUCE, SATIETY_REDUCE};
+use crate::achievement::{Achievement, REWARD_GOLD, REWARD_PROP};
+use crate::activity::{Activity, ACTIVITY_ELF_ADULT, ACTIVITY_ELF_SICK, ACTIVITY_UPKEEP_UNPAID};
+use crate::elf::{Elf, ElfPityCount, RemovedElf};
+use crate::food::Food;
+use crate::buff::{BUFF_GOLD, BUFF_GROWTH};
+use crate::event_type::{ADD_EXP, ADD_GOLD, ADD_SHIT, BUFF_EXPIRE, HEALTH_ADD, HEALTH_REDUCE, RANCH_UPKEEP, SATIETY_REDUCE};
+use crate::error::{
+    ERROR_MAX_ELF, ERROR_NOT_FOUND_RANCH, ERROR_PROP_COUNT_OVERFLOW, ERROR_REMOVED_ELF_NOT_FOUND,
+};
 use crate::events::Event;
-use crate::prop::{UserProp};
-use crate::ranch::{Ranch, RanchSlot};
+use crate::leaderboard::{Leaderboard, LEADERBOARD_ADULT};
+use crate::prop::{Prop, UserProp};
+use crate::quest::QUEST_TYPE_COUNT;
+use crate::ranch::{Ranch, RanchSlot, RanchUpkeep};
+use crate::state::State;
+use crate::supply::Supply;
 use crate::StorageData;
 use crate::{Player};
 use serde::Serialize;
 use std::slice::IterMut;
 
+// 存储格式版本头，旧数据第一位是 gold_count，不会达到这个值
+const PLAYER_DATA_VERSION_TAG: u64 = 0xffff_0000_0000_0000;
+// 当前存储格式版本
+// 1: 玩家背包
+// 2: 牧场增益
+// 3: 精灵品质保底计数
+// 4: 赠送金币和提现记录
+// 5: 牧场维护费状态
+// 6: 成就
+// 7: 每日任务
+// 8: 签到
+// 9: 冻结状态和被移除的精灵
+// 10: 牧场等级
+const PLAYER_DATA_VERSION: u64 = 10;
+
+// 新用户赠送的金币，不可提现
+const INIT_GIFT_GOLD: u64 = 10000;
+
 #[derive(Debug, Serialize)]
 pub struct PlayerData {
     pub gold_count: u64,    // 累计金币数量
@@ -16,6 +45,21 @@ pub struct PlayerData {
     pub health_count: u64,  // 累计治疗次数
     pub gold_balance: u64,  // 金币余额
     pub ranchs: Vec<Ranch>, // 拥有的牧场
+    pub props: Vec<UserProp>, // 玩家背包道具，所有牧场共用
+    pub pity: Vec<ElfPityCount>, // 每种精灵的保底计数
+    pub gift_gold: u64,     // 余额中赠送的金币，不可提现，消费时优先扣除
+    pub withdraw_day: u64,  // 最近一次提现的游戏日
+    pub withdraw_today: u64, // 当天已提现金币
+    pub adult_count: u64,   // 累计养成年的精灵数量
+    pub achievements_unlocked: u64, // 已达成的成就位图
+    pub achievements_claimed: u64,  // 已领取奖励的成就位图
+    pub quest_day: u64,     // 每日任务进度所属的游戏日
+    pub quest_progress: Vec<u64>, // 当天各类型任务的进度
+    pub quest_claimed: u64, // 当天已领取奖励的任务位图
+    pub checkin_day: u64,   // 最近一次签到的游戏日
+    pub checkin_streak: u64, // 连续签到天数，0 表示从未签到
+    pub frozen: bool,       // 被管理员冻结，不能执行任何操作
+    pub removed_elfs: Vec<RemovedElf>, // 被管理员移除的精灵
 }
 
 impl Default for PlayerData {
@@ -25,8 +69,23 @@ impl Default for PlayerData {
             clean_count: 0,
             feed_count: 0,
             health_count: 0,
-            gold_balance: 10000, // 新用户默认给120个金币
+            gold_balance: INIT_GIFT_GOLD, // 新用户默认给10000个金币
             ranchs: vec![],
+            props: vec![],
+            pity: vec![],
+            gift_gold: INIT_GIFT_GOLD,
+            withdraw_day: 0,
+            withdraw_today: 0,
+            adult_count: 0,
+            achievements_unlocked: 0,
+            achievements_claimed: 0,
+            quest_day: 0,
+            quest_progress: vec![0; QUEST_TYPE_COUNT],
+            quest_claimed: 0,
+            checkin_day: 0,
+            checkin_streak: 0,
+            frozen: false,
+            removed_elfs: vec![],
         }
     }
 }
@@ -40,6 +99,38 @@ impl PlayerData {
         ranch.elfs.iter_mut().find(|e| e.id == elf_id)
     }
 
+    // 管理员移除精灵，保留数据用于恢复
+    pub fn remove_elf_by_admin(&mut self, ranch_id: u64, elf_id: u64) -> bool {
+        let elf = match self.get_elf_mut(ranch_id, elf_id) {
+            Some(elf) => elf.clone(),
+            None => return false,
+        };
+        self.remove_elf_mut(ranch_id, elf_id);
+        self.removed_elfs.push(RemovedElf { ranch_id, elf });
+        true
+    }
+
+    // 恢复被管理员移除的精灵，id 已被占用时分配新的 id，返回恢复后的精灵
+    // reserved 为等待开奖的购买预留的槽位
+    pub fn restore_elf_by_admin(&mut self, ranch_id: u64, elf_id: u64, reserved: u64) -> Result<Elf, u32> {
+        let index = self
+            .removed_elfs
+            .iter()
+            .position(|r| r.ranch_id == ranch_id && r.elf.id == elf_id)
+            .ok_or(ERROR_REMOVED_ELF_NOT_FOUND)?;
+        let ranch = self.get_ranch_mut(ranch_id).ok_or(ERROR_NOT_FOUND_RANCH)?;
+        if ranch.elfs.len() as u64 + reserved >= ranch.elf_slot {
+            return Err(ERROR_MAX_ELF);
+        }
+        let mut elf = self.removed_elfs.remove(index).elf;
+        let ranch = self.get_ranch_mut(ranch_id).unwrap();
+        if ranch.elfs.iter().any(|e| e.id == elf.id) {
+            elf.id = ranch.elfs.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+        }
+        ranch.elfs.push(elf.clone());
+        Ok(elf)
+    }
+
     // 根据牧场id获得牧场
     pub fn get_ranch_mut(&mut self, ranch_id: u64) -> Option<&mut Ranch> {
         // 在玩家的牧场中查找匹配的牧场
@@ -52,8 +143,9 @@ impl PlayerData {
         if let Some(ranch) = self.ranchs.iter_mut().find(|r| r.id == ranch_id) {
             // 在该牧场中查找指定的精灵
             if let Some(_) = ranch.elfs.iter_mut().find(|e| e.id == elf_id) {
-                // 移除精灵
+                // 移除精灵，以及精灵身上的增益
                 ranch.elfs.retain(|e| e.id != elf_id);
+                ranch.buffs.retain(|b| b.elf_id != elf_id);
             } else {
                 return false;
             }
@@ -85,14 +177,35 @@ impl PlayerData {
     }
 
     // 喂养宠物
-    pub fn feed_elf(&mut self, ranch_id: u64, elf_id: u64, prop_type: u64) {
+    pub fn feed_elf(&mut self, player_id: &[u64; 2], ranch_id: u64, elf_id: u64, food: &Food, counter: u64) {
         if let Some(elf) = self.get_elf_mut(ranch_id, elf_id) {
-            let can_add_satiety = Elf::compute_need_add_satiety(prop_type, elf.clone());
+            let current_exp = elf.exp;
+            let can_add_satiety = Elf::compute_need_add_satiety(food, elf.clone());
             elf.satiety += can_add_satiety;
+            // 食物的额外效果
+            let can_add_exp = Elf::compute_food_add_exp(food, elf.clone());
+            elf.exp += can_add_exp;
+            let can_add_gold = Elf::compute_food_add_gold(food, elf.clone());
+            elf.current_gold_store += can_add_gold;
+            Supply::mint(can_add_gold);
             zkwasm_rust_sdk::dbg!("feed elf! add {:} \n",can_add_satiety);
+            self.check_elf_adult(player_id, ranch_id, elf_id, current_exp, counter);
         }
     }
 
+    // 经验值增加后检查精灵是否刚成年，记录动态、成年数量、成就和排行榜
+    // current_exp 为增加前的经验值，之前已经成年的不重复记录
+    fn check_elf_adult(&mut self, player_id: &[u64; 2], ranch_id: u64, elf_id: u64, current_exp: u64, counter: u64) {
+        let is_adult = self.get_elf_mut(ranch_id, elf_id).map_or(false, |elf| elf.exp >= 10000);
+        if current_exp >= 10000 || !is_adult {
+            return;
+        }
+        Activity::record(player_id, ACTIVITY_ELF_ADULT, counter, [ranch_id, elf_id, 0]);
+        self.adult_count += 1;
+        self.check_achievements();
+        Leaderboard::update(LEADERBOARD_ADULT, player_id, self.adult_count);
+    }
+
     // 治疗宠物
     pub fn healing_elf(&mut self, ranch_id: u64, elf_id: u64, prop_type: u64) {
         if let Some(elf) = self.get_elf_mut(ranch_id, elf_id) {
@@ -102,17 +215,35 @@ impl PlayerData {
         }
     }
 
-    // 扣除道具
-    pub fn reduce_prop(&mut self, ranch_id: u64, prop_type: u64) {
-        if let Some(ranch) = self.ranchs.iter_mut().find(|r| r.id == ranch_id) {
-            if let Some(prop) = ranch.props.iter_mut().find(|p| p.prop_type == prop_type) {
-                prop.count -= 1;
-                zkwasm_rust_sdk::dbg!("reduce_prop! \n");
-                return;
-            }
+    // 扣除背包道具
+    pub fn reduce_prop(&mut self, prop_type: u64) {
+        if let Some(prop) = self.props.iter_mut().find(|p| p.prop_type == prop_type) {
+            prop.count -= 1;
+            zkwasm_rust_sdk::dbg!("reduce_prop! \n");
         }
     }
 
+    // 背包添加道具，同类道具的数量溢出时返回错误，背包不变
+    pub fn add_prop(&mut self, user_prop: UserProp) -> Result<(), u32> {
+        if let Some(prop) = self
+            .props
+            .iter_mut()
+            .find(|p| p.prop_type == user_prop.prop_type)
+        {
+            prop.count = prop.count.checked_add(user_prop.count).ok_or(ERROR_PROP_COUNT_OVERFLOW)?;
+            zkwasm_rust_sdk::dbg!("add prop! \n");
+        } else {
+            self.props.push(user_prop);
+            zkwasm_rust_sdk::dbg!("save prop! \n");
+        }
+        Ok(())
+    }
+
+    // 获取背包道具
+    pub fn get_prop_by_type(&mut self, prop_type: u64) -> Option<&mut UserProp> {
+        self.props.iter_mut().find(|p| p.prop_type == prop_type)
+    }
+
     // 指定牧场，添加宠物
     pub fn set_elf_by_ranch(&mut self, ranch_id: u64, elf: Elf) {
         if let Some(ranch) = self.ranchs.iter_mut().find(|r| r.id == ranch_id) {
@@ -121,7 +252,7 @@ impl PlayerData {
         }
     }
 
-    // 指定牧场，添加道具
+    // 指定牧场，放置道具
     pub fn set_prop_by_ranch(&mut self, ranch_id: u64, user_prop: UserProp) {
         if let Some(ranch) = self.ranchs.iter_mut().find(|r| r.id == ranch_id) {
             if let Some(prop) = ranch
@@ -129,21 +260,171 @@ impl PlayerData {
                 .iter_mut()
                 .find(|p| p.prop_type == user_prop.prop_type)
             {
-                prop.count += 1;
-                zkwasm_rust_sdk::dbg!("add prop! \n");
+                prop.count += user_prop.count;
+                zkwasm_rust_sdk::dbg!("place prop! \n");
                 return;
             } else {
                 ranch.props.push(user_prop);
-                zkwasm_rust_sdk::dbg!("save prop! \n");
+                zkwasm_rust_sdk::dbg!("place new prop! \n");
             }
         }
     }
 
-    pub fn get_prop_by_type(&mut self, ranch_id: u64, prop_type: u64) -> Option<&mut UserProp> {
-        if let Some(ranch) = self.ranchs.iter_mut().find(|r| r.id == ranch_id) {
-            return ranch.props.iter_mut().find(|p| p.prop_type == prop_type);
+    // 消费金币，优先扣除赠送的金币，调用前需要检查余额
+    pub fn spend_gold(&mut self, amount: u64) {
+        self.deduct_gold(amount);
+        Supply::burn(amount);
+    }
+
+    // 扣除金币，优先扣除赠送的金币，不计入金币统计，由调用方记录扣除的原因
+    fn deduct_gold(&mut self, amount: u64) {
+        self.gold_balance -= amount;
+        self.gift_gold = self.gift_gold.saturating_sub(amount);
+    }
+
+    // 拥有的指定品质的精灵数量
+    pub fn get_elf_count_by_grade(&self, grade: u64) -> u64 {
+        self.ranchs
+            .iter()
+            .map(|r| r.elfs.iter().filter(|e| e.grade == grade).count() as u64)
+            .sum()
+    }
+
+    // 精灵收藏分数，先比较拥有的最高品质，再比较最高品质精灵的数量
+    // 分数为 最高品质 << 32 | 最高品质精灵数量，没有精灵时为 0
+    pub fn get_collection_score(&self) -> u64 {
+        let top_grade = self
+            .ranchs
+            .iter()
+            .flat_map(|r| r.elfs.iter().map(|e| e.grade))
+            .max()
+            .unwrap_or(0);
+        if top_grade == 0 {
+            return 0;
         }
-        None
+        top_grade << 32 | self.get_elf_count_by_grade(top_grade)
+    }
+
+    // 检查并记录新达成的成就，计数变化后调用
+    pub fn check_achievements(&mut self) {
+        for achievement in Achievement::get_all_achievements() {
+            if self.achievements_unlocked & achievement.mask() == 0 && achievement.is_reached(self) {
+                self.achievements_unlocked |= achievement.mask();
+            }
+        }
+    }
+
+    // 领取成就奖励，调用前需要检查成就已达成且未领取
+    pub fn claim_achievement(&mut self, achievement: &Achievement) -> Result<(), u32> {
+        self.achievements_claimed |= achievement.mask();
+        self.add_reward(achievement.reward_type, achievement.reward_value, achievement.reward_count)
+    }
+
+    // 发放奖励，金币直接进入余额，道具放入背包
+    pub fn add_reward(&mut self, reward_type: u64, reward_value: u64, reward_count: u64) -> Result<(), u32> {
+        match reward_type {
+            REWARD_GOLD => {
+                self.gold_balance += reward_value;
+                Supply::reward(reward_value);
+            }
+            REWARD_PROP => {
+                self.add_prop(UserProp::new(reward_value, reward_count))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // 签到，返回签到后的连续天数，当天已经签到返回 None
+    pub fn check_in(&mut self, day: u64) -> Option<u64> {
+        if self.checkin_streak > 0 && self.checkin_day == day {
+            return None;
+        }
+        // 昨天签到过则连续天数加一，否则重新开始
+        if self.checkin_streak > 0 && self.checkin_day + 1 == day {
+            self.checkin_streak += 1;
+        } else {
+            self.checkin_streak = 1;
+        }
+        self.checkin_day = day;
+        Some(self.checkin_streak)
+    }
+
+    // 切换到新的游戏日时重置每日任务
+    pub fn reset_daily_quests(&mut self, day: u64) {
+        if self.quest_day != day {
+            self.quest_day = day;
+            self.quest_progress = vec![0; QUEST_TYPE_COUNT];
+            self.quest_claimed = 0;
+        }
+    }
+
+    // 增加当天的任务进度
+    pub fn add_quest_progress(&mut self, quest_type: u64, amount: u64, day: u64) {
+        self.reset_daily_quests(day);
+        if let Some(progress) = self.quest_progress.get_mut((quest_type as usize).wrapping_sub(1)) {
+            *progress += amount;
+        }
+    }
+
+    // 当天某类型任务的进度
+    pub fn get_quest_progress(&self, quest_type: u64) -> u64 {
+        self.quest_progress
+            .get((quest_type as usize).wrapping_sub(1))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    // 可以提现的金币
+    pub fn get_withdrawable_gold(&self) -> u64 {
+        self.gold_balance - self.gift_gold
+    }
+
+    // 获取精灵类型的保底计数
+    pub fn get_pity_count(&self, elf_type: u64) -> u64 {
+        self.pity
+            .iter()
+            .find(|p| p.elf_type == elf_type)
+            .map_or(0, |p| p.count)
+    }
+
+    // 根据抽到的等级更新保底计数，达到保底等级后清零
+    pub fn update_pity_count(&mut self, elf_type: u64, grade: u64) {
+        let count = if grade >= Elf::get_pity().min_grade {
+            0
+        } else {
+            self.get_pity_count(elf_type) + 1
+        };
+        if let Some(pity) = self.pity.iter_mut().find(|p| p.elf_type == elf_type) {
+            pity.count = count;
+        } else {
+            self.pity.push(ElfPityCount { elf_type, count });
+        }
+    }
+
+    // 旧数据迁移：把牧场中非放置类的道具合并到玩家背包
+    fn migrate_ranch_props(&mut self) {
+        let mut moved = vec![];
+        for ranch in self.ranchs.iter_mut() {
+            let (placed, stacks): (Vec<UserProp>, Vec<UserProp>) = ranch
+                .props
+                .drain(..)
+                .partition(|p| Prop::is_placeable(p.prop_type));
+            ranch.props = placed;
+            moved.extend(stacks);
+        }
+        // 旧数据中各牧场同类道具的数量相加，不会接近上限
+        for user_prop in moved {
+            let _ = self.add_prop(user_prop);
+        }
+    }
+
+    // 获取精灵当前生效的增益系数
+    pub fn get_buff_modifier(&self, ranch_id: u64, elf_id: u64, buff_type: u64, counter: u64) -> u64 {
+        self.ranchs
+            .iter()
+            .find(|r| r.id == ranch_id)
+            .map_or(100, |r| r.get_buff_modifier(elf_id, buff_type, counter))
     }
 
     // 宠物增加经验
@@ -153,27 +434,29 @@ impl PlayerData {
         event_type: u64,
         ranch_id: u64,
         elf_id: u64,
+        counter: u64,
     ) -> Option<Event> {
         zkwasm_rust_sdk::dbg!("add exp ranch_id : {:?} ,elf_id: {:?}\n", ranch_id, elf_id);
+        let growth_modifier = self.get_buff_modifier(ranch_id, elf_id, BUFF_GROWTH, counter);
         // 尝试获取精灵的可变引用
-        if let Some(elf) = self.get_elf_mut(ranch_id, elf_id) {
-            let current_elf = elf.clone();
-            let growth_time = current_elf.growth_time;
-            let current_exp = current_elf.exp;
-            let added_exp = Elf::compute_need_exp(growth_time, current_exp);
-            elf.exp += added_exp;
-            zkwasm_rust_sdk::dbg!("add exp is {:?} \n", added_exp);
-            // 如果经验值未达到 10000，返回 Event；否则返回 None
-            if elf.exp < 10000 {
-                return Some(Event {
-                    owner: player_id,
-                    event_type,
-                    ranch_id,
-                    elf_id,
-                    delta: 1, // 每5秒触发一次的加经验值
-                });
-            }
+        let elf = self.get_elf_mut(ranch_id, elf_id)?;
+        let current_elf = elf.clone();
+        let growth_time = current_elf.growth_time;
+        let current_exp = current_elf.exp;
+        let added_exp = Elf::compute_need_exp(growth_time, current_exp, growth_modifier);
+        elf.exp += added_exp;
+        zkwasm_rust_sdk::dbg!("add exp is {:?} \n", added_exp);
+        // 如果经验值未达到 10000，返回 Event；否则返回 None
+        if elf.exp < 10000 {
+            return Some(Event {
+                owner: player_id,
+                event_type,
+                ranch_id,
+                elf_id,
+                delta: 1, // 每5秒触发一次的加经验值
+            });
         }
+        self.check_elf_adult(&player_id, ranch_id, elf_id, current_exp, counter);
         None
     }
 
@@ -184,14 +467,18 @@ impl PlayerData {
         event_type: u64,
         ranch_id: u64,
         elf_id: u64,
+        counter: u64,
     ) -> Option<Event> {
         zkwasm_rust_sdk::dbg!("elf_add_gold_event \n");
+        let gold_modifier = self.get_buff_modifier(ranch_id, elf_id, BUFF_GOLD, counter);
+        let upkeep_factor = self.get_ranch_mut(ranch_id).map_or(100, |r| r.get_upkeep_factor());
         // 尝试获取精灵的可变引用
         if let Some(elf) = self.get_elf_mut(ranch_id, elf_id) {
             let current_elf = elf.clone();
-            let add_gold = Elf::compute_need_gold(current_elf);
+            let add_gold = Elf::compute_need_gold(current_elf, gold_modifier, upkeep_factor, counter);
             zkwasm_rust_sdk::dbg!("add gold is {:?} \n", add_gold);
             elf.current_gold_store += add_gold;
+            Supply::mint(add_gold);
             // 如果经验值未达到 10000，返回 Event；否则返回 None
             if elf.current_gold_store < elf.max_gold_store {
                 return Some(Event {
@@ -206,12 +493,16 @@ impl PlayerData {
         None
     }
 
-    // 获取牧场槽位价格
-    pub fn get_ranch_slot_price(&self, ranch_id: u64) -> u64 {
-        let ranch = self.ranchs.iter().find(|r| r.id == ranch_id).unwrap();
+    // 获取牧场下一个槽位的价格，按牧场等级的价格配置
+    pub fn get_ranch_slot_price(&self, ranch_id: u64) -> Result<u64, u32> {
+        let ranch = self
+            .ranchs
+            .iter()
+            .find(|r| r.id == ranch_id)
+            .ok_or(ERROR_NOT_FOUND_RANCH)?;
         let slot_count = ranch.elf_slot;
         let need_count_id = slot_count + 1;
-        RanchSlot::get_price_by_id(need_count_id)
+        RanchSlot::get_price_by_id(ranch.tier, need_count_id)
     }
 
     // 增加牧场槽位
@@ -228,6 +519,7 @@ impl PlayerData {
         event_type: u64,
         ranch_id: u64,
         elf_id: u64,
+        counter: u64,
     ) -> Option<Event> {
         zkwasm_rust_sdk::dbg!(
             "Starting elf_health_reduce_event: ranch_id={:?}, elf_id={:?}\n",
@@ -266,6 +558,10 @@ impl PlayerData {
             // 更新精灵健康值
             elf.health -= health_reduce;
         } else {
+            // 健康度刚归零时记录动态
+            if elf.health > 0 {
+                Activity::record(&player_id, ACTIVITY_ELF_SICK, counter, [ranch_id, elf_id, 0]);
+            }
             elf.health = 0;
         }
 
@@ -390,20 +686,98 @@ impl PlayerData {
         })
     }
 
+    // 清除过期的增益，还有未过期的增益则继续等待
+    pub fn buff_expire_event(
+        &mut self,
+        owner: [u64; 2],
+        event_type: u64,
+        ranch_id: u64,
+        elf_id: u64,
+        counter: u64,
+    ) -> Option<Event> {
+        zkwasm_rust_sdk::dbg!("buff_expire_event \n");
+        let ranch = self.get_ranch_mut(ranch_id)?;
+        ranch
+            .buffs
+            .retain(|b| !(b.elf_id == elf_id && !b.is_active(counter)));
+        let next_expire = ranch
+            .buffs
+            .iter()
+            .filter(|b| b.elf_id == elf_id)
+            .map(|b| b.expire_at)
+            .min()?;
+        Some(Event {
+            owner,
+            event_type,
+            ranch_id,
+            elf_id,
+            delta: (next_expire - counter) as usize,
+        })
+    }
+
+    // 扣除牧场维护费，余额不足时牧场进入欠费状态
+    // 牧场没有精灵时停止扣费，再次购买精灵时重新开始
+    pub fn ranch_upkeep_event(
+        &mut self,
+        owner: [u64; 2],
+        event_type: u64,
+        ranch_id: u64,
+        counter: u64,
+    ) -> Option<Event> {
+        zkwasm_rust_sdk::dbg!("ranch_upkeep_event \n");
+        let upkeep = RanchUpkeep::get_upkeep();
+        let ranch = self.get_ranch_mut(ranch_id)?;
+        if ranch.elfs.is_empty() {
+            ranch.upkeep_unpaid = false;
+            return None;
+        }
+        // 游戏暂停时玩家无法收取金币和操作，不扣费也不降低产出，到下一次扣费时再检查
+        if State::is_game_paused() {
+            ranch.upkeep_unpaid = false;
+            return Some(Event {
+                owner,
+                event_type,
+                ranch_id,
+                elf_id: 0,
+                delta: upkeep.interval as usize,
+            });
+        }
+        let cost = upkeep.compute_cost(ranch);
+        let paid = self.gold_balance >= cost;
+        if paid {
+            self.deduct_gold(cost);
+            Supply::upkeep(cost);
+        } else {
+            Activity::record(&owner, ACTIVITY_UPKEEP_UNPAID, counter, [ranch_id, cost, 0]);
+        }
+        self.get_ranch_mut(ranch_id)?.upkeep_unpaid = !paid;
+        zkwasm_rust_sdk::dbg!("ranch upkeep cost {:?} paid {:?}\n", cost, paid);
+        Some(Event {
+            owner,
+            event_type,
+            ranch_id,
+            elf_id: 0,
+            delta: upkeep.interval as usize,
+        })
+    }
+
     pub fn event_hand(
         &mut self,
         player_id: [u64; 2],
         event_type: u64,
         ranch_id: u64,
         elf_id: u64,
+        counter: u64,
     ) -> Option<Event> {
         let event = match event_type {
-            ADD_EXP => self.elf_add_exp_event(player_id, event_type, ranch_id, elf_id),
-            ADD_GOLD => self.elf_add_gold_event(player_id, event_type, ranch_id, elf_id),
-            HEALTH_REDUCE => self.elf_health_reduce_event(player_id, event_type, ranch_id, elf_id),
+            ADD_EXP => self.elf_add_exp_event(player_id, event_type, ranch_id, elf_id, counter),
+            ADD_GOLD => self.elf_add_gold_event(player_id, event_type, ranch_id, elf_id, counter),
+            HEALTH_REDUCE => self.elf_health_reduce_event(player_id, event_type, ranch_id, elf_id, counter),
             SATIETY_REDUCE => self.elf_satiety_reduce_event(player_id, event_type, ranch_id, elf_id),
             ADD_SHIT => self.add_shit_event(player_id, event_type, ranch_id, elf_id),
             HEALTH_ADD => self.add_health_event(player_id, event_type, ranch_id, elf_id),
+            BUFF_EXPIRE => self.buff_expire_event(player_id, event_type, ranch_id, elf_id, counter),
+            RANCH_UPKEEP => self.ranch_upkeep_event(player_id, event_type, ranch_id, counter),
             _ => None,
         };
         event
@@ -412,8 +786,15 @@ impl PlayerData {
 
 impl StorageData for PlayerData {
     fn from_data(u64data: &mut IterMut<u64>) -> Self {
+        // 读取版本头，没有版本头的是旧数据
+        let head = *u64data.next().unwrap();
+        let (version, gold_count) = if head & PLAYER_DATA_VERSION_TAG == PLAYER_DATA_VERSION_TAG {
+            (head & !PLAYER_DATA_VERSION_TAG, *u64data.next().unwrap())
+        } else {
+            (0, head)
+        };
+
         // 读取基础数据
-        let gold_count = *u64data.next().unwrap();
         let clean_count = *u64data.next().unwrap();
         let feed_count = *u64data.next().unwrap();
         let health_count = *u64data.next().unwrap();
@@ -423,21 +804,119 @@ impl StorageData for PlayerData {
         let ranchs_count = *u64data.next().unwrap() as usize; // 读取道具数量
         let mut ranchs = Vec::with_capacity(ranchs_count);
         for _ in 0..ranchs_count {
-            let ranch = Ranch::from_data(u64data); // 假设 Prop 类型也有 from_data 方法
+            let ranch = Ranch::from_data(u64data, version); // 假设 Prop 类型也有 from_data 方法
             ranchs.push(ranch);
         }
 
-        PlayerData {
+        // 读取背包道具
+        let mut props = vec![];
+        if version >= 1 {
+            let props_count = *u64data.next().unwrap() as usize;
+            for _ in 0..props_count {
+                props.push(UserProp::from_data(u64data));
+            }
+        }
+
+        // 读取保底计数
+        let mut pity = vec![];
+        if version >= 3 {
+            let pity_count = *u64data.next().unwrap() as usize;
+            for _ in 0..pity_count {
+                pity.push(ElfPityCount::from_data(u64data));
+            }
+        }
+
+        // 读取赠送金币和提现记录，旧数据按赠送金币全部未消费处理
+        let mut gift_gold = gold_balance.min(INIT_GIFT_GOLD);
+        let mut withdraw_day = 0;
+        let mut withdraw_today = 0;
+        if version >= 4 {
+            gift_gold = *u64data.next().unwrap();
+            withdraw_day = *u64data.next().unwrap();
+            withdraw_today = *u64data.next().unwrap();
+        }
+
+        // 读取成就，旧数据按当前成年的精灵统计
+        let mut adult_count = ranchs
+            .iter()
+            .map(|r| r.elfs.iter().filter(|e| e.exp >= 10000).count() as u64)
+            .sum();
+        let mut achievements_unlocked = 0;
+        let mut achievements_claimed = 0;
+        if version >= 6 {
+            adult_count = *u64data.next().unwrap();
+            achievements_unlocked = *u64data.next().unwrap();
+            achievements_claimed = *u64data.next().unwrap();
+        }
+
+        // 读取每日任务
+        let mut quest_day = 0;
+        let mut quest_progress = vec![0; QUEST_TYPE_COUNT];
+        let mut quest_claimed = 0;
+        if version >= 7 {
+            quest_day = *u64data.next().unwrap();
+            let progress_count = *u64data.next().unwrap() as usize;
+            quest_progress = Vec::with_capacity(progress_count.max(QUEST_TYPE_COUNT));
+            for _ in 0..progress_count {
+                quest_progress.push(*u64data.next().unwrap());
+            }
+            // 新增的任务类型从 0 开始
+            quest_progress.resize(progress_count.max(QUEST_TYPE_COUNT), 0);
+            quest_claimed = *u64data.next().unwrap();
+        }
+
+        // 读取签到记录
+        let mut checkin_day = 0;
+        let mut checkin_streak = 0;
+        if version >= 8 {
+            checkin_day = *u64data.next().unwrap();
+            checkin_streak = *u64data.next().unwrap();
+        }
+
+        // 读取冻结状态和被移除的精灵
+        let mut frozen = false;
+        let mut removed_elfs = vec![];
+        if version >= 9 {
+            frozen = *u64data.next().unwrap() != 0;
+            let removed_count = *u64data.next().unwrap() as usize;
+            for _ in 0..removed_count {
+                removed_elfs.push(RemovedElf::from_data(u64data));
+            }
+        }
+
+        let mut player_data = PlayerData {
             gold_count,
             clean_count,
             feed_count,
             health_count,
             gold_balance,
             ranchs,
+            props,
+            pity,
+            gift_gold,
+            withdraw_day,
+            withdraw_today,
+            adult_count,
+            achievements_unlocked,
+            achievements_claimed,
+            quest_day,
+            quest_progress,
+            quest_claimed,
+            checkin_day,
+            checkin_streak,
+            frozen,
+            removed_elfs,
+        };
+        if version < 1 {
+            player_data.migrate_ranch_props();
         }
+        player_data
     }
 
     fn to_data(&self, data: &mut Vec<u64>) {
+        // 写入版本头
+        data.push(PLAYER_DATA_VERSION_TAG | PLAYER_DATA_VERSION);
+
         // 将基础数据推入数据流
         data.push(self.gold_count);
         data.push(self.clean_count);
@@ -450,6 +929,47 @@ impl StorageData for PlayerData {
         for ranch in &self.ranchs {
             ranch.to_data(data); // 使用 Ranch 的 to_data 方法将每个牧场转回数据
         }
+
+        // 将背包道具推入数据流
+        data.push(self.props.len() as u64);
+        for prop in &self.props {
+            prop.to_data(data);
+        }
+
+        // 将保底计数推入数据流
+        data.push(self.pity.len() as u64);
+        for pity in &self.pity {
+            pity.to_data(data);
+        }
+
+        // 赠送金币和提现记录
+        data.push(self.gift_gold);
+        data.push(self.withdraw_day);
+        data.push(self.withdraw_today);
+
+        // 成就
+        data.push(self.adult_count);
+        data.push(self.achievements_unlocked);
+        data.push(self.achievements_claimed);
+
+        // 每日任务
+        data.push(self.quest_day);
+        data.push(self.quest_progress.len() as u64);
+        for progress in &self.quest_progress {
+            data.push(*progress);
+        }
+        data.push(self.quest_claimed);
+
+        // 签到
+        data.push(self.checkin_day);
+        data.push(self.checkin_streak);
+
+        // 冻结状态和被移除的精灵
+        data.push(self.frozen as u64);
+        data.push(self.removed_elfs.len() as u64);
+        for removed in &self.removed_elfs {
+            removed.to_data(data);
+        }
     }
 }
 
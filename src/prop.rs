@@ -1,6 +1,7 @@
 use serde::Serialize;
 use std::slice::IterMut;
 use crate::StorageData;
+use crate::config::ConfigOverride;
 
 
 
@@ -21,8 +22,37 @@ pub const Cabbage: (u64,u64) =(5,75);
 // 治疗剂
 pub const Healing_Potion: (u64,u64) =(6,150);
 
+// 蜂蜜
+pub const Honey: (u64,u64) =(7,300);
+
+// 金色四叶草
+pub const Golden_Clover: (u64,u64) =(8,500);
+
+// 成长药剂
+pub const Growth_Elixir: (u64,u64) =(9,400);
+
 pub const price_type_usdt :u64= 1;
 pub const price_type_gold :u64= 2;
+
+// 单次最多购买数量
+pub const MAX_BUY_COUNT: u64 = 999;
+
+// 批量购买折扣，购买数量达到 min_count 时减免 discount%
+#[derive(Clone, Debug, Serialize)]
+pub struct PropDiscount {
+    pub min_count: u64,
+    pub discount: u64,
+}
+
+impl PropDiscount {
+    pub fn new(min_count: u64, discount: u64) -> Self {
+        PropDiscount {
+            min_count,
+            discount,
+        }
+    }
+}
+
 // 道具
 #[derive(Clone, Debug, Serialize)]
 pub struct Prop {
@@ -32,10 +62,12 @@ pub struct Prop {
     pub price: u64,
     pub price_type: u64,
     pub prop_type: u64,
+    pub placeable: bool, // 是否需要放置到牧场中使用
+    pub discounts: Vec<PropDiscount>, // 批量购买折扣
 }
 
 impl Prop {
-    pub fn new(id: u64, name: &'static str,desc: &'static str,price:u64,price_type:u64,prop_type:u64) -> Self {
+    pub fn new(id: u64, name: &'static str,desc: &'static str,price:u64,price_type:u64,prop_type:u64,placeable:bool,discounts:Vec<PropDiscount>) -> Self {
         Prop {
             id,
             name,
@@ -43,9 +75,27 @@ impl Prop {
             price,
             price_type,
             prop_type,
+            placeable,
+            discounts,
         }
     }
 
+    // 计算购买 count 个道具的总价，溢出返回 None
+    pub fn get_total_price(&self, count: u64) -> Option<u64> {
+        let price = ConfigOverride::get_prop_price(self.prop_type, self.price);
+        let total = price.checked_mul(count)?;
+        // 取满足数量条件的最大折扣
+        let discount = self
+            .discounts
+            .iter()
+            .filter(|d| count >= d.min_count)
+            .map(|d| d.discount)
+            .max()
+            .unwrap_or(0);
+        let reduce = total.checked_mul(discount)? / 100;
+        Some(total - reduce)
+    }
+
     pub fn get_all_pops() -> &'static Vec<Prop> {
         &*PROP_LIST
     }
@@ -54,6 +104,11 @@ impl Prop {
     pub fn get_prop_by_type(prop_type:u64) -> Option<&'static Prop> {
         PROP_LIST.iter().find(|p| p.prop_type == prop_type)
     }
+
+    // 是否是放置在牧场中的道具
+    pub fn is_placeable(prop_type:u64) -> bool {
+        Prop::get_prop_by_type(prop_type).map_or(false, |p| p.placeable)
+    }
 }
 
 impl StorageData for Prop {
@@ -84,6 +139,14 @@ impl StorageData for Prop {
         let price = *u64data.next().unwrap();
         let price_type = *u64data.next().unwrap();
         let prop_type = *u64data.next().unwrap();
+        let placeable = *u64data.next().unwrap() != 0;
+        let discounts_count = *u64data.next().unwrap() as usize;
+        let mut discounts = Vec::with_capacity(discounts_count);
+        for _ in 0..discounts_count {
+            let min_count = *u64data.next().unwrap();
+            let discount = *u64data.next().unwrap();
+            discounts.push(PropDiscount::new(min_count, discount));
+        }
         Prop {
             id,
             name:Box::leak(name.into_boxed_str()),
@@ -91,6 +154,8 @@ impl StorageData for Prop {
             price,
             price_type,
             prop_type,
+            placeable,
+            discounts,
         }
     }
 
@@ -131,6 +196,12 @@ impl StorageData for Prop {
         data.push(self.price); // 价格
         data.push(self.price_type); // 价格类型
         data.push(self.prop_type); // 类型
+        data.push(self.placeable as u64); // 是否放置类道具
+        data.push(self.discounts.len() as u64); // 折扣档位
+        for discount in &self.discounts {
+            data.push(discount.min_count);
+            data.push(discount.discount);
+        }
     }
 }
 
@@ -141,10 +212,10 @@ pub struct UserProp {
 }
 
 impl UserProp {
-    pub fn new(prop_type: u64) -> Self {
+    pub fn new(prop_type: u64, count: u64) -> Self {
         UserProp {
             prop_type,
-            count:1,
+            count,
         }
     }
 }
@@ -169,12 +240,45 @@ impl StorageData for UserProp {
 lazy_static::lazy_static! {
     pub static ref PROP_LIST: Vec<Prop> = {
         vec![
-            Prop::new(1,"Magic Broom","Magic Broom",Magic_Broom.1,price_type_usdt,Magic_Broom.0),
-            Prop::new(2,"Bugu House","Bugu House",Bugu_House.1,price_type_usdt,Bugu_House.0),
-            Prop::new(3,"Money Hive","Money Hive",Money_Hive.1,price_type_usdt,Money_Hive.0),
-            Prop::new(4,"Carrot","Carrot",Carrot.1,price_type_gold,Carrot.0),
-            Prop::new(5,"Cabbage","Cabbage",Cabbage.1,price_type_gold,Cabbage.0),
-            Prop::new(6,"Healing Potion","Healing Potion",Healing_Potion.1,price_type_gold,Healing_Potion.0),
+            Prop::new(1,"Magic Broom","Magic Broom",Magic_Broom.1,price_type_usdt,Magic_Broom.0,true,vec![]),
+            Prop::new(2,"Bugu House","Bugu House",Bugu_House.1,price_type_usdt,Bugu_House.0,true,vec![]),
+            Prop::new(3,"Money Hive","Money Hive",Money_Hive.1,price_type_usdt,Money_Hive.0,true,vec![]),
+            Prop::new(4,"Carrot","Carrot",Carrot.1,price_type_gold,Carrot.0,false,vec![PropDiscount::new(10,5),PropDiscount::new(50,10)]),
+            Prop::new(5,"Cabbage","Cabbage",Cabbage.1,price_type_gold,Cabbage.0,false,vec![PropDiscount::new(10,5),PropDiscount::new(50,10)]),
+            Prop::new(6,"Healing Potion","Healing Potion",Healing_Potion.1,price_type_gold,Healing_Potion.0,false,vec![PropDiscount::new(10,5)]),
+            Prop::new(7,"Honey","Honey",Honey.1,price_type_gold,Honey.0,false,vec![]),
+            Prop::new(8,"Golden Clover","Double gold production for one hour",Golden_Clover.1,price_type_gold,Golden_Clover.0,false,vec![]),
+            Prop::new(9,"Growth Elixir","Grow 1.5x faster for one hour",Growth_Elixir.1,price_type_gold,Growth_Elixir.0,false,vec![]),
         ]
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_price_without_discount() {
+        let honey = Prop::get_prop_by_type(Honey.0).unwrap();
+        assert_eq!(honey.get_total_price(1), Some(Honey.1));
+        assert_eq!(honey.get_total_price(10), Some(Honey.1 * 10));
+    }
+
+    #[test]
+    fn total_price_uses_highest_reached_discount() {
+        let carrot = Prop::get_prop_by_type(Carrot.0).unwrap();
+        // 不满 10 个没有折扣
+        assert_eq!(carrot.get_total_price(9), Some(45));
+        // 10 个 5% 折扣
+        assert_eq!(carrot.get_total_price(10), Some(48));
+        // 50 个取 10% 折扣，不叠加 5%
+        assert_eq!(carrot.get_total_price(50), Some(225));
+        assert_eq!(carrot.get_total_price(MAX_BUY_COUNT), Some(4496));
+    }
+
+    #[test]
+    fn total_price_overflow() {
+        let carrot = Prop::get_prop_by_type(Carrot.0).unwrap();
+        assert_eq!(carrot.get_total_price(u64::MAX), None);
+    }
+}
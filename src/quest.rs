@@ -0,0 +1,81 @@
+use crate::achievement::{REWARD_GOLD, REWARD_PROP};
+use crate::prop::{Cabbage, Carrot, Healing_Potion, Honey};
+use serde::Serialize;
+
+// 每日任务类型，对应玩家当天的任务进度下标 quest_type - 1
+pub const QUEST_FEED: u64 = 1; // 喂食次数
+pub const QUEST_COLLECT_GOLD: u64 = 2; // 收集金币数量
+pub const QUEST_CLEAN: u64 = 3; // 清洁次数
+pub const QUEST_TYPE_COUNT: usize = 3;
+
+// 每天开放的任务数量
+pub const DAILY_QUEST_COUNT: u64 = 3;
+
+// 每日任务，id 对应玩家当天已领取位图中的位置，最多 64 个
+#[derive(Clone, Debug, Serialize)]
+pub struct Quest {
+    pub id: u64,
+    pub name: &'static str,
+    pub quest_type: u64,   // 任务类型
+    pub target: u64,       // 需要完成的数量
+    pub reward_type: u64,  // 奖励类型
+    pub reward_value: u64, // 金币数量或道具类型
+    pub reward_count: u64, // 道具数量，奖励金币时为 1
+}
+
+impl Quest {
+    pub fn new(
+        id: u64,
+        name: &'static str,
+        quest_type: u64,
+        target: u64,
+        reward_type: u64,
+        reward_value: u64,
+        reward_count: u64,
+    ) -> Self {
+        Quest {
+            id,
+            name,
+            quest_type,
+            target,
+            reward_type,
+            reward_value,
+            reward_count,
+        }
+    }
+
+    pub fn get_all_quests() -> &'static Vec<Quest> {
+        &*QUEST_LIST
+    }
+
+    // 游戏日开放的任务，按天依次轮换列表中连续的 DAILY_QUEST_COUNT 个任务
+    // 只依赖游戏日，证明中可以重放
+    pub fn get_daily_quests(day: u64) -> Vec<&'static Quest> {
+        let len = QUEST_LIST.len() as u64;
+        let start = day * DAILY_QUEST_COUNT % len;
+        (0..DAILY_QUEST_COUNT.min(len))
+            .map(|i| &QUEST_LIST[((start + i) % len) as usize])
+            .collect()
+    }
+
+    // 当天开放的任务中查找
+    pub fn get_daily_quest_by_id(day: u64, id: u64) -> Option<&'static Quest> {
+        Quest::get_daily_quests(day).into_iter().find(|q| q.id == id)
+    }
+
+    // 任务在位图中的位置
+    pub fn mask(&self) -> u64 {
+        1 << self.id
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref QUEST_LIST: Vec<Quest> = vec![
+        Quest::new(0, "Feed 5 times", QUEST_FEED, 5, REWARD_PROP, Carrot.0, 10),
+        Quest::new(1, "Collect 500 gold", QUEST_COLLECT_GOLD, 500, REWARD_GOLD, 100, 1),
+        Quest::new(2, "Clean 3 times", QUEST_CLEAN, 3, REWARD_PROP, Cabbage.0, 2),
+        Quest::new(3, "Feed 20 times", QUEST_FEED, 20, REWARD_PROP, Honey.0, 1),
+        Quest::new(4, "Collect 5000 gold", QUEST_COLLECT_GOLD, 5000, REWARD_GOLD, 800, 1),
+        Quest::new(5, "Clean 5 times", QUEST_CLEAN, 5, REWARD_PROP, Healing_Potion.0, 1),
+    ];
+}
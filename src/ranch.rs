@@ -1,7 +1,10 @@
 use std::slice::IterMut;
 use serde::Serialize;
 use zkwasm_rest_abi::StorageData;
+use crate::buff::Buff;
+use crate::config::{ConfigOverride, TICKS_PER_DAY};
 use crate::elf::Elf;
+use crate::error::{ERROR_RANCH_SLOT_NOT_FOUND, ERROR_RANCH_TIER_NOT_FOUND};
 use crate::prop::{Prop, UserProp, PROP_LIST};
 
 #[derive(Debug,Serialize, Clone)]
@@ -10,7 +13,10 @@ pub struct Ranch {
     pub ranch_clean: u64, // 牧场清洁度
     pub elf_slot: u64, // 牧场槽位
     pub elfs: Vec<Elf>, // 拥有的精灵
-    pub props: Vec<UserProp>,   // 拥有的道具 ，道具类型，数量
+    pub props: Vec<UserProp>,   // 放置在牧场中的道具 ，道具类型，数量
+    pub buffs: Vec<Buff>, // 牧场中精灵的增益
+    pub upkeep_unpaid: bool, // 上一次维护费是否未支付
+    pub tier: u64, // 牧场等级，决定槽位上限和槽位价格
 }
 
 impl Ranch {
@@ -33,8 +39,17 @@ impl Ranch {
         for prop in &self.props {
             prop.to_data(data);
         }
+
+        data.push(self.buffs.len() as u64);
+        for buff in &self.buffs {
+            buff.to_data(data);
+        }
+
+        data.push(self.upkeep_unpaid as u64);
+        data.push(self.tier);
     }
-    pub fn from_data(u64data: &mut IterMut<u64>) -> Self {
+    // version 为玩家数据的存储格式版本
+    pub fn from_data(u64data: &mut IterMut<u64>, version: u64) -> Self {
         let id = *u64data.next().unwrap();
         let ranch_clean = *u64data.next().unwrap();
         let elf_slot = *u64data.next().unwrap();
@@ -53,12 +68,60 @@ impl Ranch {
             props.push(prop);
         }
 
+        let mut buffs = vec![];
+        if version >= 2 {
+            let buffs_count = *u64data.next().unwrap() as usize;
+            for _ in 0..buffs_count {
+                buffs.push(Buff::from_data(u64data));
+            }
+        }
+
+        let upkeep_unpaid = if version >= 5 {
+            *u64data.next().unwrap() != 0
+        } else {
+            false
+        };
+
+        // 旧数据中的牧场都是基础等级
+        let tier = if version >= 10 {
+            *u64data.next().unwrap()
+        } else {
+            RANCH_TIER_BASIC
+        };
+
         Ranch {
             id,
             ranch_clean,
             elf_slot,
             elfs,
-            props
+            props,
+            buffs,
+            upkeep_unpaid,
+            tier
+        }
+    }
+
+    // 获取精灵当前生效的增益系数，没有增益返回 100
+    pub fn get_buff_modifier(&self, elf_id: u64, buff_type: u64, counter: u64) -> u64 {
+        self.buffs
+            .iter()
+            .find(|b| b.elf_id == elf_id && b.buff_type == buff_type && b.is_active(counter))
+            .map_or(100, |b| b.modifier)
+    }
+
+    // 添加增益，同类型的增益会被覆盖
+    pub fn set_buff(&mut self, buff: Buff) {
+        self.buffs
+            .retain(|b| !(b.elf_id == buff.elf_id && b.buff_type == buff.buff_type));
+        self.buffs.push(buff);
+    }
+
+    // 维护费未支付时的产出系数，正常为 100
+    pub fn get_upkeep_factor(&self) -> u64 {
+        if self.upkeep_unpaid {
+            RanchUpkeep::get_upkeep().unpaid_factor
+        } else {
+            100
         }
     }
 }
@@ -70,7 +133,10 @@ impl Ranch {
             elf_slot:1,
             ranch_clean:0,
             elfs:vec![],
-            props:vec![]
+            props:vec![],
+            buffs:vec![],
+            upkeep_unpaid:false,
+            tier:RANCH_TIER_BASIC
         }
     }
 }
@@ -102,24 +168,61 @@ impl RanchSlot {
         }
     }
 
-    pub fn get_all_ranch_slots() -> &'static Vec<RanchSlot> {
-        &*RANCH_SLOT_LIST
+    // 牧场等级中第 id 个槽位的价格，没有配置时返回错误
+    pub fn get_price_by_id(tier: u64, id: u64) -> Result<u64, u32> {
+        let ranch_tier = RanchTier::get_tier(tier)?;
+        ranch_tier
+            .slots
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| ConfigOverride::get_slot_price(tier, s.id, s.price))
+            .ok_or(ERROR_RANCH_SLOT_NOT_FOUND)
     }
+}
 
-    pub fn get_price_by_id(id: u64) -> u64 {
-        let slots = RanchSlot::get_all_ranch_slots();
-        for slot in slots {
-            if slot.id == id {
-                return slot.price;
-            }
+// 牧场等级
+pub const RANCH_TIER_BASIC: u64 = 1; // 初始牧场
+pub const RANCH_TIER_ADVANCED: u64 = 2; // 高级牧场
+
+// 牧场等级的槽位上限和每个槽位的价格，槽位 id 为购买后的槽位数量
+#[derive(Debug,Serialize, Clone)]
+pub struct RanchTier {
+    pub tier: u64,
+    pub upgrade_price: u64, // 从上一等级升级到这个等级需要的金币
+    pub max_slot: u64,
+    pub slots: Vec<RanchSlot>,
+}
+
+impl RanchTier {
+    pub fn new(tier: u64, upgrade_price: u64, max_slot: u64, slots: Vec<RanchSlot>) -> Self {
+        RanchTier {
+            tier,
+            upgrade_price,
+            max_slot,
+            slots
         }
-        30000000000000
+    }
+
+    pub fn get_all_tiers() -> &'static Vec<RanchTier> {
+        &*RANCH_TIER_LIST
+    }
+
+    // 基础等级的槽位，列表第一项是基础等级
+    pub fn get_basic_slots() -> &'static Vec<RanchSlot> {
+        &RANCH_TIER_LIST[0].slots
+    }
+
+    pub fn get_tier(tier: u64) -> Result<&'static RanchTier, u32> {
+        RANCH_TIER_LIST
+            .iter()
+            .find(|t| t.tier == tier)
+            .ok_or(ERROR_RANCH_TIER_NOT_FOUND)
     }
 }
 
 lazy_static::lazy_static! {
-    pub static ref RANCH_SLOT_LIST: Vec<RanchSlot> = {
-        vec![
+    pub static ref RANCH_TIER_LIST: Vec<RanchTier> = {
+        let basic_slots = vec![
             RanchSlot::new(2,500),
             RanchSlot::new(3,1000),
             RanchSlot::new(4,1500),
@@ -129,6 +232,46 @@ lazy_static::lazy_static! {
             RanchSlot::new(8,7000),
             RanchSlot::new(9,10000),
             RanchSlot::new(10,15000),
+        ];
+        let mut advanced_slots = basic_slots.clone();
+        advanced_slots.extend(vec![
+            RanchSlot::new(11,20000),
+            RanchSlot::new(12,26000),
+            RanchSlot::new(13,33000),
+            RanchSlot::new(14,41000),
+            RanchSlot::new(15,50000),
+        ]);
+        vec![
+            RanchTier::new(RANCH_TIER_BASIC, 0, 10, basic_slots),
+            RanchTier::new(RANCH_TIER_ADVANCED, 30000, 15, advanced_slots),
         ]
     };
-}
\ No newline at end of file
+}
+// 牧场维护费规则，按槽位和精灵数量定期扣除金币
+#[derive(Debug,Serialize, Clone)]
+pub struct RanchUpkeep {
+    pub interval: u64,      // 扣费间隔，单位 tick
+    pub slot_cost: u64,     // 每个槽位的维护费
+    pub elf_cost: u64,      // 每只精灵的维护费
+    pub unpaid_factor: u64, // 未支付时精灵的金币产出系数，以 100 为 1.0
+}
+
+impl RanchUpkeep {
+    pub fn get_upkeep() -> &'static RanchUpkeep {
+        &*RANCH_UPKEEP
+    }
+
+    // 计算牧场一次的维护费
+    pub fn compute_cost(&self, ranch: &Ranch) -> u64 {
+        ranch.elf_slot * self.slot_cost + ranch.elfs.len() as u64 * self.elf_cost
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref RANCH_UPKEEP: RanchUpkeep = RanchUpkeep {
+        interval: TICKS_PER_DAY / 24, // 每小时
+        slot_cost: 5,
+        elf_cost: 10,
+        unpaid_factor: 50, // 0.5
+    };
+}
@@ -0,0 +1,124 @@
+use crate::error::{ERROR_INVALID_SEASON, ERROR_INVALID_SEASON_MODIFIER, ERROR_SEASON_OVERLAP};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::slice::IterMut;
+
+// 活动系数的取值范围，超出范围的活动不能登记
+pub const SEASON_MIN_GOLD_MODIFIER: u64 = 50; // 产出最低 0.5 倍
+pub const SEASON_MAX_GOLD_MODIFIER: u64 = 300; // 产出最高 3 倍
+pub const SEASON_MIN_PRICE_MODIFIER: u64 = 50; // 道具最低 5 折
+pub const SEASON_MAX_PRICE_MODIFIER: u64 = 200; // 道具最高 2 倍价格
+pub const SEASON_MAX_GRADE_BONUS: u64 = 10; // 抽卡随机数（1 - 100）最多加 10
+// 最多同时登记的活动数量，包括正在进行和还没开始的
+pub const MAX_SEASON_COUNT: usize = 8;
+
+// 赛季活动，在 [start, end) 的 counter 区间内生效，系数以 100 为 1.0
+// 精灵产出金币的事件里也要读取活动系数，所以不放在 STATE 中，随 State 的扩展数据一起保存
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Season {
+    pub id: u64,
+    pub start: u64,          // 开始的 counter
+    pub end: u64,            // 结束的 counter
+    pub gold_modifier: u64,  // 精灵金币产出系数
+    pub price_modifier: u64, // 金币购买道具的价格系数
+    pub grade_bonus: u64,    // 抽卡随机数增加的点数，提高高品质概率
+}
+
+// 登记的活动按开始时间排序，时间区间互不重叠
+pub struct SafeSeason(RefCell<Vec<Season>>);
+unsafe impl Sync for SafeSeason {}
+
+lazy_static::lazy_static! {
+    pub static ref SEASON: SafeSeason = SafeSeason(RefCell::new(vec![]));
+}
+
+impl Season {
+    // 检查活动时间和系数
+    pub fn validate(&self, counter: u64) -> Result<(), u32> {
+        if self.end <= self.start || self.end <= counter {
+            return Err(ERROR_INVALID_SEASON);
+        }
+        let gold_valid = (SEASON_MIN_GOLD_MODIFIER..=SEASON_MAX_GOLD_MODIFIER).contains(&self.gold_modifier);
+        let price_valid = (SEASON_MIN_PRICE_MODIFIER..=SEASON_MAX_PRICE_MODIFIER).contains(&self.price_modifier);
+        if !gold_valid || !price_valid || self.grade_bonus > SEASON_MAX_GRADE_BONUS {
+            return Err(ERROR_INVALID_SEASON_MODIFIER);
+        }
+        Ok(())
+    }
+
+    // 登记赛季活动，已经结束的活动会被清理，和已登记的活动时间重叠时拒绝
+    pub fn add(season: Season, counter: u64) -> Result<(), u32> {
+        season.validate(counter)?;
+        let mut seasons = SEASON.0.borrow_mut();
+        seasons.retain(|s| s.end > counter);
+        if seasons.iter().any(|s| season.start < s.end && s.start < season.end) {
+            return Err(ERROR_SEASON_OVERLAP);
+        }
+        if seasons.len() >= MAX_SEASON_COUNT {
+            return Err(ERROR_INVALID_SEASON);
+        }
+        let index = seasons.iter().position(|s| s.start > season.start).unwrap_or(seasons.len());
+        seasons.insert(index, season);
+        Ok(())
+    }
+
+    // 当前生效的赛季活动
+    pub fn get_active(counter: u64) -> Option<Season> {
+        SEASON
+            .0
+            .borrow()
+            .iter()
+            .find(|s| counter >= s.start && counter < s.end)
+            .cloned()
+    }
+
+    // 正在进行和还没开始的活动
+    pub fn get_scheduled(counter: u64) -> Vec<Season> {
+        SEASON.0.borrow().iter().filter(|s| s.end > counter).cloned().collect()
+    }
+
+    // 金币产出系数，没有活动返回 100
+    pub fn get_gold_modifier(counter: u64) -> u64 {
+        Season::get_active(counter).map_or(100, |s| s.gold_modifier)
+    }
+
+    // 道具价格系数，没有活动返回 100
+    pub fn get_price_modifier(counter: u64) -> u64 {
+        Season::get_active(counter).map_or(100, |s| s.price_modifier)
+    }
+
+    // 抽卡随机数加成，没有活动返回 0
+    pub fn get_grade_bonus(counter: u64) -> u64 {
+        Season::get_active(counter).map_or(0, |s| s.grade_bonus)
+    }
+
+    // 活动数量和每个活动的数据
+    pub fn to_data(data: &mut Vec<u64>) {
+        let seasons = SEASON.0.borrow();
+        data.push(seasons.len() as u64);
+        for s in seasons.iter() {
+            data.push(s.id);
+            data.push(s.start);
+            data.push(s.end);
+            data.push(s.gold_modifier);
+            data.push(s.price_modifier);
+            data.push(s.grade_bonus);
+        }
+    }
+
+    pub fn from_data(u64data: &mut IterMut<u64>) {
+        let count = *u64data.next().unwrap();
+        let mut seasons = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            seasons.push(Season {
+                id: *u64data.next().unwrap(),
+                start: *u64data.next().unwrap(),
+                end: *u64data.next().unwrap(),
+                gold_modifier: *u64data.next().unwrap(),
+                price_modifier: *u64data.next().unwrap(),
+                grade_bonus: *u64data.next().unwrap(),
+            });
+        }
+        *SEASON.0.borrow_mut() = seasons;
+    }
+}
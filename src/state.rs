@@ -1,17 +1,38 @@
-use crate::config::ADMIN_PUBKEY;
-use crate::elf::{Elf, StandElf};
+use crate::achievement::Achievement;
+use crate::admin::{
+    AdminRegistry, ROLE_CONFIG_MANAGER, ROLE_DEPOSITOR, ROLE_MODERATOR, ROLE_TICKER,
+};
+use crate::audit::AuditRecord;
+use crate::activity::{
+    Activity, ACTIVITY_BUY_ELF, ACTIVITY_BUY_PROP, ACTIVITY_BUY_SLOT, ACTIVITY_CLAIM_ACHIEVEMENT,
+    ACTIVITY_CHECK_IN, ACTIVITY_CLAIM_QUEST, ACTIVITY_COLLECT_GOLD, ACTIVITY_DEPOSIT, ACTIVITY_SELL_ELF, ACTIVITY_USE_PROP,
+    ACTIVITY_UPGRADE_RANCH, ACTIVITY_WITHDRAW,
+};
+use crate::buff::{Buff, BuffProp};
+use crate::checkin::CheckInReward;
+use crate::config::{ConfigOverride, TICKS_PER_DAY};
+use crate::deposit::{DepositRecord, DEPOSIT_KIND_GOLD, DEPOSIT_KIND_PROP};
+use crate::elf::Elf;
 use crate::error::*;
-use crate::event_type::{ADD_EXP, ADD_GOLD, ADD_SHIT, HEALTH_ADD, HEALTH_REDUCE, SATIETY_REDUCE};
+use crate::event_type::{ADD_EXP, ADD_GOLD, ADD_SHIT, BUFF_EXPIRE, HEALTH_ADD, HEALTH_REDUCE, RANCH_UPKEEP, SATIETY_REDUCE};
 use crate::events::Event;
+use crate::food::Food;
+use crate::leaderboard::{Leaderboard, LEADERBOARD_COLLECTION, LEADERBOARD_GOLD};
+use crate::pending::PendingElf;
 use crate::player::ElfPlayer;
-use crate::prop::{price_type_gold, price_type_usdt, Prop, UserProp};
-use crate::ranch::Ranch;
+use crate::prop::{price_type_gold, price_type_usdt, Prop, UserProp, MAX_BUY_COUNT};
+use crate::quest::{Quest, QUEST_CLEAN, QUEST_COLLECT_GOLD, QUEST_FEED};
+use crate::ranch::{Ranch, RanchTier, RanchUpkeep};
+use crate::season::Season;
+use crate::supply::Supply;
+use crate::withdraw::WithdrawPolicy;
 use lazy_static::lazy_static;
 use std::cell::RefCell;
 use zkwasm_rest_abi::StorageData;
 use zkwasm_rest_abi::WithdrawInfo;
 use zkwasm_rest_abi::MERKLE_MAP;
 use zkwasm_rest_convention::{EventQueue, SettlementInfo};
+use zkwasm_rust_sdk::PoseidonHasher;
 /*
 // Custom serializer for `[u64; 4]` as a [String; 4].
 fn serialize_u64_array_as_string<S>(value: &[u64; 4], serializer: S) -> Result<S::Ok, S::Error>
@@ -50,6 +71,40 @@ const BUY_SLOT: u64 = 13; // 购买宠物槽位
 
 const BUY_PROP: u64 = 12; // 购买道具
 
+const PLACE_PROP: u64 = 14; // 放置道具到牧场
+
+const USE_PROP: u64 = 15; // 对精灵使用增益道具
+
+const DEPOSIT_GOLD: u64 = 16; // 充值金币
+
+const CLAIM_ACHIEVEMENT: u64 = 17; // 领取成就奖励
+
+const CLAIM_QUEST: u64 = 18; // 领取每日任务奖励
+
+const CHECK_IN: u64 = 19; // 每日签到
+
+const SET_SEASON: u64 = 20; // 登记赛季活动
+
+const SET_CONFIG: u64 = 21; // 修改配置
+
+const FREEZE_PLAYER: u64 = 22; // 冻结玩家
+const UNFREEZE_PLAYER: u64 = 23; // 解冻玩家
+const ADJUST_GOLD: u64 = 24; // 调整玩家金币
+const REMOVE_ELF: u64 = 25; // 移除玩家的精灵
+const RESTORE_ELF: u64 = 26; // 恢复被移除的精灵
+
+const ADD_ADMIN: u64 = 27; // 添加管理员或修改角色
+const REVOKE_ADMIN: u64 = 28; // 撤销管理员
+
+const SET_PAUSED: u64 = 29; // 设置暂停标志
+
+const UPGRADE_RANCH: u64 = 30; // 升级牧场
+
+// 暂停标志位
+const PAUSE_ALL: u64 = 1; // 暂停所有玩家操作
+const PAUSE_WITHDRAW: u64 = 2; // 暂停提现
+const PAUSE_PURCHASE: u64 = 4; // 暂停购买精灵、道具、槽位和牧场
+
 impl Transaction {
     pub fn decode_error(e: u32) -> &'static str {
         match e {
@@ -66,6 +121,41 @@ impl Transaction {
             ERROR_INVALID_PURCHASE_CONDITION => "InvalidPurchaseCondition",
             ERROR_MAX_ELF_SLOT => "MaxElfSlot",
             ERROR_MUST_ADMIN_KEY => "MustAdminKey",
+            ERROR_PROP_NOT_FOOD => "PropNotFood",
+            ERROR_PROP_NOT_PLACEABLE => "PropNotPlaceable",
+            ERROR_INVALID_BUY_COUNT => "InvalidBuyCount",
+            ERROR_PROP_NOT_BUFF => "PropNotBuff",
+            ERROR_WITHDRAW_TOO_SMALL => "WithdrawTooSmall",
+            ERROR_GOLD_NOT_WITHDRAWABLE => "GoldNotWithdrawable",
+            ERROR_WITHDRAW_DAILY_LIMIT => "WithdrawDailyLimit",
+            ERROR_DEPOSIT_ALREADY_PROCESSED => "DepositAlreadyProcessed",
+            ERROR_DEPOSIT_AMOUNT_MISMATCH => "DepositAmountMismatch",
+            ERROR_ACHIEVEMENT_NOT_FOUND => "AchievementNotFound",
+            ERROR_ACHIEVEMENT_LOCKED => "AchievementLocked",
+            ERROR_ACHIEVEMENT_CLAIMED => "AchievementClaimed",
+            ERROR_QUEST_NOT_FOUND => "QuestNotFound",
+            ERROR_QUEST_NOT_COMPLETED => "QuestNotCompleted",
+            ERROR_QUEST_CLAIMED => "QuestClaimed",
+            ERROR_ALREADY_CHECKED_IN => "AlreadyCheckedIn",
+            ERROR_INVALID_SEASON => "InvalidSeason",
+            ERROR_INVALID_SEASON_MODIFIER => "InvalidSeasonModifier",
+            ERROR_SEASON_OVERLAP => "SeasonOverlap",
+            ERROR_INVALID_CONFIG => "InvalidConfig",
+            ERROR_PLAYER_FROZEN => "PlayerFrozen",
+            ERROR_REMOVED_ELF_NOT_FOUND => "RemovedElfNotFound",
+            ERROR_INVALID_ADMIN => "InvalidAdmin",
+            ERROR_ADMIN_NOT_FOUND => "AdminNotFound",
+            ERROR_GAME_PAUSED => "GamePaused",
+            ERROR_WITHDRAW_PAUSED => "WithdrawPaused",
+            ERROR_PURCHASE_PAUSED => "PurchasePaused",
+            ERROR_INVALID_PAUSE_FLAGS => "InvalidPauseFlags",
+            ERROR_RANCH_TIER_NOT_FOUND => "RanchTierNotFound",
+            ERROR_RANCH_SLOT_NOT_FOUND => "RanchSlotNotFound",
+            ERROR_MAX_RANCH_TIER => "MaxRanchTier",
+            ERROR_GOLD_OVERFLOW => "GoldOverflow",
+            ERROR_PROP_COUNT_OVERFLOW => "PropCountOverflow",
+            ERROR_INVALID_DEPOSIT_AMOUNT => "InvalidDepositAmount",
+            ERROR_PROP_NOT_HEALING => "PropNotHealing",
             _ => "Unknown",
         }
     }
@@ -77,7 +167,7 @@ impl Transaction {
         if command == WITHDRAW {
             data = vec![params[2], params[3], params[4]]
         } else if command == DEPOSIT {
-            data = vec![params[1], params[2], params[3], params[4]];
+            data = vec![params[1], params[2], params[3], params[4], params[5], params[6], params[7]];
         } else if command == BOUNTY {
             data = vec![params[1]]
         } else if command == INIT_PLAYER {
@@ -100,6 +190,32 @@ impl Transaction {
             data = vec![params[1]]
         } else if command == BUY_PROP {
             data = vec![params[1], params[2]]
+        } else if command == PLACE_PROP {
+            data = vec![params[1], params[2]]
+        } else if command == USE_PROP {
+            data = vec![params[1], params[2], params[3]]
+        } else if command == DEPOSIT_GOLD {
+            data = vec![params[1], params[2], params[3], params[4], params[5], params[6], params[7]]
+        } else if command == CLAIM_ACHIEVEMENT {
+            data = vec![params[1]]
+        } else if command == CLAIM_QUEST {
+            data = vec![params[1]]
+        } else if command == SET_SEASON {
+            data = vec![params[1], params[2], params[3], params[4]]
+        } else if command == SET_CONFIG {
+            data = vec![params[1], params[2], params[3], params[4]]
+        } else if command == FREEZE_PLAYER || command == UNFREEZE_PLAYER {
+            data = vec![params[1], params[2]]
+        } else if command == ADJUST_GOLD || command == REMOVE_ELF || command == RESTORE_ELF {
+            data = vec![params[1], params[2], params[3], params[4]]
+        } else if command == ADD_ADMIN {
+            data = vec![params[1], params[2], params[3]]
+        } else if command == REVOKE_ADMIN {
+            data = vec![params[1], params[2]]
+        } else if command == SET_PAUSED {
+            data = vec![params[1]]
+        } else if command == UPGRADE_RANCH {
+            data = vec![params[1]]
         }
 
         Transaction {
@@ -110,7 +226,7 @@ impl Transaction {
     }
 
     // 初始化用户
-    pub fn install_player(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn install_player(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let player = ElfPlayer::get_from_pid(pid);
         match player {
             Some(_) => Err(ERROR_PLAYER_ALREADY_EXIST),
@@ -124,13 +240,13 @@ impl Transaction {
                 let ranch = Ranch::new(ranch_id as u64);
                 player.data.ranchs.push(ranch);
                 player.store();
-                Ok(())
+                Ok(vec![])
             }
         }
     }
 
-    // 购买精灵
-    pub fn buy_elf(&self, pid: &[u64; 2], rand: u64) -> Result<(), u32> {
+    // 购买精灵，只扣除金币并占用槽位，品质在之后的 tick 中开奖，见 resolve_pending_elfs
+    pub fn buy_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -141,8 +257,9 @@ impl Transaction {
                 let elf_type = self.data[1];
                 if let Some(elfs_count) = player.data.get_elf_len(ranch_id) {
                     zkwasm_rust_sdk::dbg!("elfs_count {:?}\n", elfs_count);
-                    let elf_slot = player.data.get_ranch_mut(ranch_id).unwrap().elf_slot;
-                    if elfs_count == elf_slot {
+                    let elf_slot = player.data.get_ranch_mut(ranch_id).ok_or(ERROR_NOT_FOUND_RANCH)?.elf_slot;
+                    // 等待开奖的购买已经占用了槽位
+                    if elfs_count + PendingElf::count(pid, ranch_id) >= elf_slot {
                         return Err(ERROR_MAX_ELF);
                     }
                     // 根据类型判断是否符合购买条件，并返回价格
@@ -161,19 +278,18 @@ impl Transaction {
                     }
 
                     // 减少用户的金额
-                    player.data.gold_balance -= buy_price;
-                    // 获取当前牧场的宠物数量
-                    let max_id = player.data.get_elf_last_id(ranch_id).unwrap();
-                    // 保存新宠物到牧场
-                    let new_elf = Elf::get_elf(rand, elf_type, max_id);
-                    let elf_event = new_elf.clone();
-                    player.data.set_elf_by_ranch(ranch_id, new_elf);
+                    player.data.spend_gold(buy_price);
                     player.store();
-                    zkwasm_rust_sdk::dbg!("init_event start\n");
-                    // 初始化宠物事件
-                    self.init_event(*pid, ranch_id, elf_event);
-                    zkwasm_rust_sdk::dbg!("buy elf ok \n");
-                    Ok(())
+                    PendingElf::add(PendingElf {
+                        pid: *pid,
+                        ranch_id,
+                        elf_type,
+                        price: buy_price,
+                        counter: State::get_counter(),
+                    });
+                    zkwasm_rust_sdk::dbg!("buy elf pending \n");
+                    // 返回牧场中等待开奖的数量和剩余金币
+                    Ok(vec![PendingElf::count(pid, ranch_id), player.data.gold_balance])
                 } else {
                     Err(ERROR_NOT_FOUND_RANCH)
                 }
@@ -182,44 +298,113 @@ impl Transaction {
     }
 
     // 购买道具
-    pub fn buy_prop(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn buy_prop(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
             Some(player) => {
                 player.check_and_inc_nonce(self.nonce);
-                // 获取牧场id
-                let ranch_id = self.data[0];
-                let prop_type = self.data[1];
-                {
-                    let ranch = player.data.get_ranch_mut(ranch_id);
-                    if ranch.is_none() {
-                        return Err(ERROR_NOT_FOUND_RANCH);
-                    }
+                let prop_type = self.data[0];
+                // 购买数量，不传默认买一个
+                let count = if self.data[1] == 0 { 1 } else { self.data[1] };
+                if count > MAX_BUY_COUNT {
+                    return Err(ERROR_INVALID_BUY_COUNT);
                 }
                 if let Some(prop) = Prop::get_prop_by_type(prop_type) {
                     if prop.price_type == price_type_gold {
+                        let total_price = match prop.get_total_price(count) {
+                            Some(total_price) => total_price,
+                            None => return Err(ERROR_INVALID_BUY_COUNT),
+                        };
+                        // 赛季活动的价格系数
+                        let price_modifier = Season::get_price_modifier(State::get_counter());
+                        let total_price = match total_price.checked_mul(price_modifier) {
+                            Some(v) => v / 100,
+                            None => return Err(ERROR_INVALID_BUY_COUNT),
+                        };
                         let gold_balance = player.data.gold_balance;
-                        if gold_balance < prop.price {
+                        if gold_balance < total_price {
                             return Err(ERROR_NOT_GOLD_BALANCE);
                         }
-                        player.data.gold_balance -= prop.price;
-                        let user_prop = UserProp::new(prop.prop_type);
-                        player.data.set_prop_by_ranch(ranch_id, user_prop);
+                        // 道具放入玩家背包，数量溢出时不扣金币
+                        let user_prop = UserProp::new(prop.prop_type, count);
+                        player.data.add_prop(user_prop)?;
+                        player.data.spend_gold(total_price);
                         player.store();
+                        let counter = State::get_counter();
+                        Activity::record(pid, ACTIVITY_BUY_PROP, counter, [prop_type, count, total_price]);
+                        // 返回剩余金币
+                        Ok(vec![player.data.gold_balance])
                     } else {
-                        return Err(ERROR_THIS_PROP_MUST_BE_USED_USDT);
+                        Err(ERROR_THIS_PROP_MUST_BE_USED_USDT)
                     }
                 } else {
-                    return Err(ERROR_NOT_FOUND_PROP);
+                    Err(ERROR_NOT_FOUND_PROP)
                 }
-                Ok(())
+            }
+        }
+    }
+
+    // 把背包中的放置类道具放到牧场
+    pub fn place_prop(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let ranch_id = self.data[0];
+                let prop_type = self.data[1];
+                {
+                    let ranch = player.data.get_ranch_mut(ranch_id);
+                    if ranch.is_none() {
+                        return Err(ERROR_NOT_FOUND_RANCH);
+                    }
+                }
+                if !Prop::is_placeable(prop_type) {
+                    return Err(ERROR_PROP_NOT_PLACEABLE);
+                }
+                if let Some(user_prop) = player.data.get_prop_by_type(prop_type) {
+                    if user_prop.count == 0 {
+                        return Err(ERROR_NOT_FOUND_PROP);
+                    }
+                    player.data.reduce_prop(prop_type);
+                    player.data.set_prop_by_ranch(ranch_id, UserProp::new(prop_type, 1));
+                    player.store();
+                    Ok(vec![])
+                } else {
+                    Err(ERROR_NOT_FOUND_PROP)
+                }
+            }
+        }
+    }
+
+    // 升级牧场到下一等级，提高槽位上限，需要牧场id
+    pub fn upgrade_ranch(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let ranch_id = self.data[0];
+                let tier = player.data.get_ranch_mut(ranch_id).ok_or(ERROR_NOT_FOUND_RANCH)?.tier;
+                let next_tier = RanchTier::get_tier(tier + 1).map_err(|_| ERROR_MAX_RANCH_TIER)?;
+                let price = next_tier.upgrade_price;
+                if player.data.gold_balance < price {
+                    return Err(ERROR_NOT_GOLD_BALANCE);
+                }
+                player.data.spend_gold(price);
+                player.data.get_ranch_mut(ranch_id).unwrap().tier = next_tier.tier;
+                player.store();
+                let counter = State::get_counter();
+                Activity::record(pid, ACTIVITY_UPGRADE_RANCH, counter, [ranch_id, next_tier.tier, price]);
+                // 返回新的等级和剩余金币
+                Ok(vec![next_tier.tier, player.data.gold_balance])
             }
         }
     }
 
     // 购买精灵槽位
-    pub fn buy_slot(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn buy_slot(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -232,26 +417,32 @@ impl Transaction {
                     if ranch.is_none() {
                         return Err(ERROR_NOT_FOUND_RANCH);
                     }
-                    if ranch.unwrap().elf_slot == 10 {
+                    // 槽位上限由牧场等级决定
+                    let ranch = ranch.unwrap();
+                    if ranch.elf_slot >= RanchTier::get_tier(ranch.tier)?.max_slot {
                         return Err(ERROR_MAX_ELF_SLOT);
                     }
                 }
 
-                let slot_price = player.data.get_ranch_slot_price(ranch_id);
+                let slot_price = player.data.get_ranch_slot_price(ranch_id)?;
                 let gold_balance = player.data.gold_balance.clone();
                 if gold_balance < slot_price {
                     return Err(ERROR_NOT_GOLD_BALANCE);
                 }
-                player.data.gold_balance -= slot_price;
+                player.data.spend_gold(slot_price);
                 player.data.add_ranch_elf_slot(ranch_id);
                 player.store();
-                Ok(())
+                // 返回新的槽位数量和剩余金币
+                let elf_slot = player.data.get_ranch_mut(ranch_id).unwrap().elf_slot;
+                let counter = State::get_counter();
+                Activity::record(pid, ACTIVITY_BUY_SLOT, counter, [ranch_id, elf_slot, slot_price]);
+                Ok(vec![elf_slot, player.data.gold_balance])
             }
         }
     }
 
     // 收集金币，需要牧场id和精灵id
-    pub fn collect_gold(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn collect_gold(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -266,10 +457,17 @@ impl Transaction {
                     elf.current_gold_store = 0;
                     player.data.gold_balance += gold;
                     player.data.gold_count += gold;
+                    player.data.check_achievements();
+                    let day = State::get_counter() / TICKS_PER_DAY;
+                    player.data.add_quest_progress(QUEST_COLLECT_GOLD, gold, day);
                     player.store();
+                    Leaderboard::update(LEADERBOARD_GOLD, pid, player.data.gold_count);
                     // 初始化宠物事件
                     self.init_event(*pid, ranch_id, elf_event);
-                    Ok(())
+                    let counter = State::get_counter();
+                    Activity::record(pid, ACTIVITY_COLLECT_GOLD, counter, [ranch_id, elf_id, gold]);
+                    // 返回收集的金币和金币余额
+                    Ok(vec![gold, player.data.gold_balance])
                 } else {
                     Err(ERROR_NOT_FOUND_ELF)
                 }
@@ -278,7 +476,7 @@ impl Transaction {
     }
 
     // 清洁牧场
-    pub fn clean_ranch(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn clean_ranch(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -291,12 +489,15 @@ impl Transaction {
                         let elfs = ranch.clone().elfs;
                         ranch.ranch_clean = 0;
                         player.data.clean_count += 1;
+                        player.data.check_achievements();
+                        let day = State::get_counter() / TICKS_PER_DAY;
+                        player.data.add_quest_progress(QUEST_CLEAN, 1, day);
                         player.store();
                         for elf in elfs {
                             self.init_event(*pid, ranch_id, elf.clone());
                         }
                     }
-                    Ok(())
+                    Ok(vec![])
                 } else {
                     Err(ERROR_NOT_FOUND_ELF)
                 }
@@ -304,6 +505,36 @@ impl Transaction {
         }
     }
 
+    // tick 中给之前的购买开奖，保底和赛季加成按开奖时计算
+    // 在事件队列 tick 之后调用，这时可以给新精灵初始化事件
+    fn resolve_pending_elfs(&self, seed: &[u64; 4]) {
+        let counter = State::get_counter();
+        for pending in PendingElf::take_all() {
+            let pid = pending.pid;
+            let ranch_id = pending.ranch_id;
+            let mut player = match ElfPlayer::get_from_pid(&pid) {
+                Some(player) => player,
+                None => continue,
+            };
+            // 购买时检查过牧场，槽位也已经预留
+            let max_id = match player.data.get_elf_last_id(ranch_id) {
+                Some(max_id) => max_id,
+                None => continue,
+            };
+            let rand = State::get_draw_rand(seed, &pid, ranch_id, max_id + 1);
+            let pity_count = player.data.get_pity_count(pending.elf_type);
+            let new_elf = Elf::get_elf(rand, pending.elf_type, max_id, pity_count, counter);
+            player.data.update_pity_count(pending.elf_type, new_elf.grade);
+            player.data.set_elf_by_ranch(ranch_id, new_elf.clone());
+            player.data.check_achievements();
+            player.store();
+            Leaderboard::update(LEADERBOARD_COLLECTION, &pid, player.data.get_collection_score());
+            // 初始化宠物事件
+            self.init_event(pid, ranch_id, new_elf.clone());
+            Activity::record(&pid, ACTIVITY_BUY_ELF, counter, [ranch_id, new_elf.id, new_elf.grade]);
+        }
+    }
+
     // 初始化事件
     pub fn init_event(&self, player_id: [u64; 2], ranch_id: u64, elf: Elf) {
         let mut state = STATE.0.borrow_mut();
@@ -313,10 +544,26 @@ impl Transaction {
         self.init_add_gold_event(&mut state, &player_id, ranch_id, elf.clone());
         self.init_add_shit_event(&mut state, &player_id, ranch_id, elf.clone());
         self.init_add_health_event(&mut state, &player_id, ranch_id, elf.clone());
+        self.init_ranch_upkeep_event(&mut state, &player_id, ranch_id);
         // todo 道具检查事件
         // todo 自动消耗金币治疗宠物，自动收集金币，自动清理牧场
     }
 
+    // 初始化牧场维护费事件，每个牧场只有一个，elf_id 为 0
+    pub fn init_ranch_upkeep_event(&self, state: &mut State, pid: &[u64; 2], ranch_id: u64) {
+        let event = Event {
+            owner: *pid,
+            event_type: RANCH_UPKEEP,
+            ranch_id,
+            elf_id: 0,
+            delta: RanchUpkeep::get_upkeep().interval as usize,
+        };
+        let is_exits = state.queue.list.contains(&event);
+        if !is_exits {
+            state.queue.insert(event);
+        }
+    }
+
     // 初始化添加金币事件
     pub fn init_add_gold_event(
         &self,
@@ -453,7 +700,7 @@ impl Transaction {
     }
 
     // 喂食精灵
-    pub fn feed_elf(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn feed_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -465,18 +712,28 @@ impl Transaction {
                 let elf = player.data.get_elf_mut(ranch_id, elf_id);
                 if let Some(elf) = elf {
                     let elf_event = elf.clone();
-                    if let Some(user_prop) = player.data.get_prop_by_type(ranch_id, prop_type) {
+                    // 只有食物可以喂食，避免消耗掉其他道具
+                    let food = match Food::get_food_by_type(prop_type) {
+                        Some(food) => food,
+                        None => return Err(ERROR_PROP_NOT_FOOD),
+                    };
+                    if let Some(user_prop) = player.data.get_prop_by_type(prop_type) {
                         zkwasm_rust_sdk::dbg!("user_prop {:?}\n", user_prop);
                         if user_prop.count == 0 {
                             return Err(ERROR_NOT_FOUND_PROP);
                         }
-                        player.data.feed_elf(ranch_id, elf_id, prop_type);
-                        player.data.reduce_prop(ranch_id, prop_type);
+                        let counter = State::get_counter();
+                        player.data.feed_elf(pid, ranch_id, elf_id, food, counter);
+                        player.data.reduce_prop(prop_type);
                         player.data.feed_count += 1;
+                        player.data.check_achievements();
+                        let day = counter / TICKS_PER_DAY;
+                        player.data.add_quest_progress(QUEST_FEED, 1, day);
                         player.store();
                         // 初始化宠物事件
                         self.init_event(*pid, ranch_id, elf_event);
-                        Ok(())
+                        Activity::record(pid, ACTIVITY_USE_PROP, counter, [ranch_id, elf_id, prop_type]);
+                        Ok(vec![])
                     } else {
                         Err(ERROR_NOT_FOUND_PROP)
                     }
@@ -488,7 +745,7 @@ impl Transaction {
     }
 
     // 治疗精灵
-    pub fn healing_elf(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn healing_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -500,17 +757,24 @@ impl Transaction {
                 let elf = player.data.get_elf_mut(ranch_id, elf_id);
                 if let Some(elf) = elf {
                     let elf_event = elf.clone();
-                    if let Some(user_prop) = player.data.get_prop_by_type(ranch_id, prop_type) {
+                    // 只有治疗道具可以治疗，避免消耗掉其他道具
+                    if !Elf::is_healing_prop(prop_type) {
+                        return Err(ERROR_PROP_NOT_HEALING);
+                    }
+                    if let Some(user_prop) = player.data.get_prop_by_type(prop_type) {
                         if user_prop.count == 0 {
                             return Err(ERROR_NOT_FOUND_PROP);
                         }
                         player.data.healing_elf(ranch_id, elf_id, prop_type);
-                        player.data.reduce_prop(ranch_id, prop_type);
+                        player.data.reduce_prop(prop_type);
                         player.data.health_count += 1;
+                        player.data.check_achievements();
                         player.store();
                         // 初始化宠物事件
                         self.init_event(*pid, ranch_id, elf_event);
-                        Ok(())
+                        let counter = State::get_counter();
+                        Activity::record(pid, ACTIVITY_USE_PROP, counter, [ranch_id, elf_id, prop_type]);
+                        Ok(vec![])
                     } else {
                         Err(ERROR_NOT_FOUND_PROP)
                     }
@@ -521,8 +785,71 @@ impl Transaction {
         }
     }
 
-    // 卖出精灵
-    pub fn sell_elf(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    // 对精灵使用增益道具
+    pub fn use_prop(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let ranch_id = self.data[0];
+                let elf_id = self.data[1];
+                let prop_type = self.data[2];
+                if player.data.get_elf_mut(ranch_id, elf_id).is_none() {
+                    return Err(ERROR_NOT_FOUND_ELF);
+                }
+                let buff_prop = match BuffProp::get_buff_by_prop_type(prop_type) {
+                    Some(buff_prop) => buff_prop,
+                    None => return Err(ERROR_PROP_NOT_BUFF),
+                };
+                if let Some(user_prop) = player.data.get_prop_by_type(prop_type) {
+                    if user_prop.count == 0 {
+                        return Err(ERROR_NOT_FOUND_PROP);
+                    }
+                    // 过期时间取事件队列的 counter
+                    let counter = State::get_counter();
+                    let expire_at = counter + buff_prop.duration;
+                    let buff = Buff::new(elf_id, buff_prop.buff_type, buff_prop.modifier, expire_at);
+                    player.data.get_ranch_mut(ranch_id).unwrap().set_buff(buff);
+                    player.data.reduce_prop(prop_type);
+                    player.store();
+                    Activity::record(pid, ACTIVITY_USE_PROP, counter, [ranch_id, elf_id, prop_type]);
+                    // 增益过期事件
+                    let mut state = STATE.0.borrow_mut();
+                    self.init_buff_expire_event(&mut state, pid, ranch_id, elf_id, buff_prop.duration);
+                    Ok(vec![])
+                } else {
+                    Err(ERROR_NOT_FOUND_PROP)
+                }
+            }
+        }
+    }
+
+    // 初始化增益过期事件
+    pub fn init_buff_expire_event(
+        &self,
+        state: &mut State,
+        pid: &[u64; 2],
+        ranch_id: u64,
+        elf_id: u64,
+        duration: u64,
+    ) {
+        let event = Event {
+            owner: *pid,
+            event_type: BUFF_EXPIRE,
+            ranch_id,
+            elf_id,
+            delta: duration as usize,
+        };
+        // 已有过期事件时，由它在触发后继续等待剩余的增益
+        let is_exits = state.queue.list.contains(&event);
+        if !is_exits {
+            state.queue.insert(event);
+        }
+    }
+
+    // 卖出精灵，返回实际卖出价格和金币余额
+    pub fn sell_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
@@ -532,19 +859,26 @@ impl Transaction {
                 let elf_id = self.data[1];
                 let elf = player.data.get_elf_mut(ranch_id, elf_id);
                 if let Some(elf) = elf {
-                    let elf_type = elf.elf_type;
-                    let grade = elf.grade;
+                    // 按品质、成长和健康计算卖出价格
+                    let sell_price = Elf::compute_sell_price(elf).ok_or(ERROR_GOLD_OVERFLOW)?;
                     let elf_id = elf.id;
+                    let gold_balance = player
+                        .data
+                        .gold_balance
+                        .checked_add(sell_price)
+                        .ok_or(ERROR_GOLD_OVERFLOW)?;
                     // 移除精灵
                     let is_remove = player.data.remove_elf_mut(ranch_id, elf_id);
-                    if is_remove {
-                        // 获取精灵的卖出价格
-                        let stand_elf = StandElf::get_elf_by_type(elf_type, grade);
-                        let sell_price = stand_elf.sell_price;
-                        player.data.gold_balance += sell_price;
-                        player.store();
+                    if !is_remove {
+                        return Err(ERROR_NOT_FOUND_ELF);
                     }
-                    Ok(())
+                    player.data.gold_balance = gold_balance;
+                    player.store();
+                    Supply::sell(sell_price);
+                    Leaderboard::update(LEADERBOARD_COLLECTION, pid, player.data.get_collection_score());
+                    let counter = State::get_counter();
+                    Activity::record(pid, ACTIVITY_SELL_ELF, counter, [ranch_id, elf_id, sell_price]);
+                    Ok(vec![sell_price, player.data.gold_balance])
                 } else {
                     Err(ERROR_NOT_FOUND_ELF)
                 }
@@ -552,8 +886,120 @@ impl Transaction {
         }
     }
 
+    // 领取成就奖励，data 为 成就id
+    pub fn claim_achievement(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let achievement = match Achievement::get_achievement_by_id(self.data[0]) {
+                    Some(achievement) => achievement,
+                    None => return Err(ERROR_ACHIEVEMENT_NOT_FOUND),
+                };
+                player.data.check_achievements();
+                if player.data.achievements_unlocked & achievement.mask() == 0 {
+                    return Err(ERROR_ACHIEVEMENT_LOCKED);
+                }
+                if player.data.achievements_claimed & achievement.mask() != 0 {
+                    return Err(ERROR_ACHIEVEMENT_CLAIMED);
+                }
+                player.data.claim_achievement(achievement)?;
+                player.store();
+                let counter = State::get_counter();
+                Activity::record(
+                    pid,
+                    ACTIVITY_CLAIM_ACHIEVEMENT,
+                    counter,
+                    [achievement.id, achievement.reward_type, achievement.reward_value],
+                );
+                // 返回奖励类型、金币数量或道具类型、道具数量和金币余额
+                Ok(vec![
+                    achievement.reward_type,
+                    achievement.reward_value,
+                    achievement.reward_count,
+                    player.data.gold_balance,
+                ])
+            }
+        }
+    }
+
+    // 领取每日任务奖励，data 为 任务id，只能领取当天开放的任务
+    pub fn claim_quest(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let counter = State::get_counter();
+                let day = counter / TICKS_PER_DAY;
+                let quest = match Quest::get_daily_quest_by_id(day, self.data[0]) {
+                    Some(quest) => quest,
+                    None => return Err(ERROR_QUEST_NOT_FOUND),
+                };
+                player.data.reset_daily_quests(day);
+                if player.data.get_quest_progress(quest.quest_type) < quest.target {
+                    return Err(ERROR_QUEST_NOT_COMPLETED);
+                }
+                if player.data.quest_claimed & quest.mask() != 0 {
+                    return Err(ERROR_QUEST_CLAIMED);
+                }
+                player.data.quest_claimed |= quest.mask();
+                player.data.add_reward(quest.reward_type, quest.reward_value, quest.reward_count)?;
+                player.store();
+                Activity::record(
+                    pid,
+                    ACTIVITY_CLAIM_QUEST,
+                    counter,
+                    [quest.id, quest.reward_type, quest.reward_value],
+                );
+                // 返回奖励类型、金币数量或道具类型、道具数量和金币余额
+                Ok(vec![
+                    quest.reward_type,
+                    quest.reward_value,
+                    quest.reward_count,
+                    player.data.gold_balance,
+                ])
+            }
+        }
+    }
+
+    // 每日签到，游戏日由事件队列的 counter 计算
+    pub fn check_in(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut player = ElfPlayer::get_from_pid(pid);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.check_and_inc_nonce(self.nonce);
+                let counter = State::get_counter();
+                let day = counter / TICKS_PER_DAY;
+                let streak = match player.data.check_in(day) {
+                    Some(streak) => streak,
+                    None => return Err(ERROR_ALREADY_CHECKED_IN),
+                };
+                let reward = CheckInReward::get_reward_by_streak(streak);
+                player.data.add_reward(reward.reward_type, reward.reward_value, reward.reward_count)?;
+                player.store();
+                Activity::record(
+                    pid,
+                    ACTIVITY_CHECK_IN,
+                    counter,
+                    [streak, reward.reward_type, reward.reward_value],
+                );
+                // 返回连续签到天数、奖励类型、金币数量或道具类型、道具数量和金币余额
+                Ok(vec![
+                    streak,
+                    reward.reward_type,
+                    reward.reward_value,
+                    reward.reward_count,
+                    player.data.gold_balance,
+                ])
+            }
+        }
+    }
+
     // 提现
-    pub fn withdraw(&self, pid: &[u64; 2]) -> Result<(), u32> {
+    pub fn withdraw(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
         zkwasm_rust_sdk::dbg!("withdraw start go \n");
         let mut player = ElfPlayer::get_from_pid(pid);
         match player.as_mut() {
@@ -561,43 +1007,85 @@ impl Transaction {
             Some(player) => {
                 player.check_and_inc_nonce(self.nonce);
                 let amount = self.data[0] & 0xffffffff;
+                let policy = WithdrawPolicy::get_policy();
+                if amount < policy.min_amount {
+                    return Err(ERROR_WITHDRAW_TOO_SMALL);
+                }
                 if player.data.gold_balance < amount {
                     return Err(ERROR_NOT_GOLD_BALANCE);
                 }
-                let withdrawinfo =
-                    WithdrawInfo::new(&[self.data[0], self.data[1], self.data[2]], 0);
+                // 赠送的金币不能提现
+                if player.data.get_withdrawable_gold() < amount {
+                    return Err(ERROR_GOLD_NOT_WITHDRAWABLE);
+                }
+                // 每日限额，按事件队列的 counter 计算游戏日
+                let day = State::get_counter() / TICKS_PER_DAY;
+                let withdrawn = policy
+                    .get_withdrawn_after(player.data.withdraw_day, player.data.withdraw_today, day, amount)
+                    .ok_or(ERROR_WITHDRAW_DAILY_LIMIT)?;
+                // 扣除手续费后结算，手续费进入国库
+                let fee = policy.compute_fee(amount);
+                let settle_amount = amount - fee;
+                let withdrawinfo = WithdrawInfo::new(
+                    &[(self.data[0] & !0xffffffff) | settle_amount, self.data[1], self.data[2]],
+                    0,
+                );
                 SettlementInfo::append_settlement(withdrawinfo);
-                zkwasm_rust_sdk::dbg!("withdraw amount is {:?}\n", amount);
+                zkwasm_rust_sdk::dbg!("withdraw amount is {:?} fee is {:?}\n", amount, fee);
                 player.data.gold_balance -= amount;
+                player.data.withdraw_day = day;
+                player.data.withdraw_today = withdrawn;
                 player.store();
-                Ok(())
+                State::add_treasury(fee);
+                Supply::withdraw(amount);
+                Activity::record(pid, ACTIVITY_WITHDRAW, State::get_counter(), [amount, fee, 0]);
+                // 返回实际到账金额、手续费和金币余额
+                Ok(vec![settle_amount, fee, player.data.gold_balance])
             }
         }
     }
 
-    // 充值
-    pub fn deposit(&self, pid: &[u64; 2]) -> Result<(), u32> {
-        let mut admin = ElfPlayer::get_from_pid(pid).unwrap();
+    // 充值命令中的 L1 交易哈希
+    fn get_tx_hash(&self) -> [u64; 4] {
+        [self.data[3], self.data[4], self.data[5], self.data[6]]
+    }
+
+    // 充值道具，需要管理员签名
+    // params 为 [命令, 玩家pid[0], 玩家pid[1], 道具信息, L1 交易哈希（4 个 u64，大端，第一个是最高位）]
+    // 道具信息：(32 bit 支付金额 | 16 bit 数量 | 16 bit 道具类型)
+    pub fn deposit(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
         admin.check_and_inc_nonce(self.nonce);
-        let mut player = ElfPlayer::get_from_pid(&[self.data[0], self.data[1]]);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
         match player.as_mut() {
             None => Err(ERROR_PLAYER_NOT_EXIST),
             Some(player) => {
-                // 获取牧场id
-                let ranch_id = self.data[2]; // 获取ranch_id
-                let prop_type = self.data[3]; // 获取prop_type
-                zkwasm_rust_sdk::dbg!("deposit ranch_id {:?}\n", ranch_id);
-                zkwasm_rust_sdk::dbg!("deposit prop_type {:?}\n", prop_type);
-                {
-                    let ranch = player.data.get_ranch_mut(ranch_id);
-                    if ranch.is_none() {
-                        return Err(ERROR_NOT_FOUND_RANCH);
-                    }
+                let prop_type = self.data[2] & 0xffff; // 获取prop_type
+                let count = (self.data[2] >> 16) & 0xffff; // 获取数量
+                let paid_amount = self.data[2] >> 32; // 获取L1支付金额
+                let tx_hash = self.get_tx_hash(); // 获取L1交易哈希
+                zkwasm_rust_sdk::dbg!("deposit prop_type {:?} count {:?}\n", prop_type, count);
+                if count == 0 || count > MAX_BUY_COUNT {
+                    return Err(ERROR_INVALID_BUY_COUNT);
+                }
+                // 管理服务超时重试时，同一笔 L1 充值只能入账一次
+                if DepositRecord::is_processed(&tx_hash) {
+                    return Err(ERROR_DEPOSIT_ALREADY_PROCESSED);
                 }
                 if let Some(prop) = Prop::get_prop_by_type(prop_type) {
                     if prop.price_type == price_type_usdt {
-                        let user_prop = UserProp::new(prop.prop_type);
-                        player.data.set_prop_by_ranch(ranch_id, user_prop);
+                        // 支付金额必须和道具总价一致
+                        if prop.get_total_price(count) != Some(paid_amount) {
+                            return Err(ERROR_DEPOSIT_AMOUNT_MISMATCH);
+                        }
+                        // 道具放入玩家背包，需要再放置到牧场
+                        let user_prop = UserProp::new(prop.prop_type, count);
+                        player.data.add_prop(user_prop)?;
+                        let counter = State::get_counter();
+                        DepositRecord::new(tx_hash, DEPOSIT_KIND_PROP, player_id, prop_type, count, counter)
+                            .store();
+                        Activity::record(&player_id, ACTIVITY_DEPOSIT, counter, [DEPOSIT_KIND_PROP, prop_type, count]);
                         player.store();
                         admin.store();
                     } else {
@@ -606,63 +1094,361 @@ impl Transaction {
                 } else {
                     return Err(ERROR_NOT_FOUND_PROP);
                 }
-                Ok(())
+                Ok(vec![])
+            }
+        }
+    }
+
+    // 充值金币，需要管理员签名
+    // params 为 [命令, 玩家pid[0], 玩家pid[1], 金额, L1 交易哈希（4 个 u64，大端，第一个是最高位）]
+    pub fn deposit_gold(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                let amount = self.data[2];
+                let tx_hash = self.get_tx_hash();
+                zkwasm_rust_sdk::dbg!("deposit gold {:?} tx {:?}\n", amount, tx_hash);
+                // 金额为 0 的充值不能占用交易哈希
+                if amount == 0 {
+                    return Err(ERROR_INVALID_DEPOSIT_AMOUNT);
+                }
+                // 同一笔 L1 充值只能入账一次
+                if DepositRecord::is_processed(&tx_hash) {
+                    return Err(ERROR_DEPOSIT_ALREADY_PROCESSED);
+                }
+                player.data.gold_balance = player
+                    .data
+                    .gold_balance
+                    .checked_add(amount)
+                    .ok_or(ERROR_GOLD_OVERFLOW)?;
+                let counter = State::get_counter();
+                DepositRecord::new(tx_hash, DEPOSIT_KIND_GOLD, player_id, amount, 1, counter).store();
+                Activity::record(&player_id, ACTIVITY_DEPOSIT, counter, [DEPOSIT_KIND_GOLD, amount, 1]);
+                player.store();
+                admin.store();
+                Supply::deposit(amount);
+                Ok(vec![])
             }
         }
     }
 
+    // 登记赛季活动，需要管理员签名，可以提前登记之后的活动，时间不能和已登记的活动重叠
+    // data 为 活动id，开始 counter，结束 counter，系数（16 bit 抽卡加成 | 16 bit 价格系数 | 16 bit 金币系数）
+    // 系数的取值范围见 season.rs
+    pub fn set_season(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let modifiers = self.data[3];
+        let season = Season {
+            id: self.data[0],
+            start: self.data[1],
+            end: self.data[2],
+            gold_modifier: modifiers & 0xffff,
+            price_modifier: (modifiers >> 16) & 0xffff,
+            grade_bonus: (modifiers >> 32) & 0xffff,
+        };
+        zkwasm_rust_sdk::dbg!("set season {:?}\n", season);
+        Season::add(season, State::get_counter())?;
+        admin.store();
+        Ok(vec![])
+    }
+
+    // 修改配置，需要管理员签名，品质概率表需要整张一起修改，格式见 ConfigOverride::get_grade_ends
+    // data 为 配置项，key，值，第二个值（只有品质概率区间使用）
+    pub fn set_config(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let table = self.data[0];
+        let key = self.data[1];
+        zkwasm_rust_sdk::dbg!("set config {:?} {:?} {:?}\n", table, key, self.data[2]);
+        if !ConfigOverride::set(table, key, [self.data[2], self.data[3]]) {
+            return Err(ERROR_INVALID_CONFIG);
+        }
+        admin.store();
+        Ok(vec![])
+    }
+
+    // 冻结或解冻玩家，需要管理员签名，data 为 玩家pid
+    pub fn set_player_frozen(&self, pid: &[u64; 2], frozen: bool) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                player.data.frozen = frozen;
+                player.store();
+                admin.store();
+                Ok(vec![])
+            }
+        }
+    }
+
+    // 调整玩家金币，需要管理员签名
+    // data 为 玩家pid，调整数量（按 i64 解释，负数为扣除），原因编码（只记录在操作记录中）
+    pub fn adjust_gold(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                let amount = self.data[2] as i64;
+                if amount >= 0 {
+                    player.data.gold_balance = player
+                        .data
+                        .gold_balance
+                        .checked_add(amount as u64)
+                        .ok_or(ERROR_GOLD_OVERFLOW)?;
+                    Supply::admin_mint(amount as u64);
+                } else {
+                    let reduce = amount.unsigned_abs();
+                    if player.data.gold_balance < reduce {
+                        return Err(ERROR_NOT_GOLD_BALANCE);
+                    }
+                    player.data.gold_balance -= reduce;
+                    player.data.gift_gold = player.data.gift_gold.min(player.data.gold_balance);
+                    Supply::admin_burn(reduce);
+                }
+                player.store();
+                admin.store();
+                // 返回调整后的金币余额
+                Ok(vec![player.data.gold_balance])
+            }
+        }
+    }
+
+    // 移除玩家的精灵，需要管理员签名，data 为 玩家pid，牧场id，精灵id
+    pub fn remove_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                if !player.data.remove_elf_by_admin(self.data[2], self.data[3]) {
+                    return Err(ERROR_NOT_FOUND_ELF);
+                }
+                player.store();
+                admin.store();
+                Leaderboard::update(LEADERBOARD_COLLECTION, &player_id, player.data.get_collection_score());
+                Ok(vec![])
+            }
+        }
+    }
+
+    // 恢复被移除的精灵，需要管理员签名，data 为 玩家pid，牧场id，精灵id
+    pub fn restore_elf(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let player_id = [self.data[0], self.data[1]];
+        let mut player = ElfPlayer::get_from_pid(&player_id);
+        match player.as_mut() {
+            None => Err(ERROR_PLAYER_NOT_EXIST),
+            Some(player) => {
+                let ranch_id = self.data[2];
+                let reserved = PendingElf::count(&player_id, ranch_id);
+                let elf = player.data.restore_elf_by_admin(ranch_id, self.data[3], reserved)?;
+                player.store();
+                admin.store();
+                Leaderboard::update(LEADERBOARD_COLLECTION, &player_id, player.data.get_collection_score());
+                // 重新开始精灵的事件
+                self.init_event(player_id, ranch_id, elf.clone());
+                // 返回恢复后的精灵id
+                Ok(vec![elf.id])
+            }
+        }
+    }
+
+    // 添加管理员，只有根管理员可以操作，data 为 管理员pid，角色
+    // 管理员需要先初始化玩家，管理员命令用玩家数据记录 nonce
+    pub fn add_admin(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let admin_id = [self.data[0], self.data[1]];
+        if ElfPlayer::get_from_pid(&admin_id).is_none() {
+            return Err(ERROR_PLAYER_NOT_EXIST);
+        }
+        if !AdminRegistry::add(admin_id, self.data[2]) {
+            return Err(ERROR_INVALID_ADMIN);
+        }
+        admin.store();
+        Ok(vec![])
+    }
+
+    // 撤销管理员，只有根管理员可以操作，data 为 管理员pid
+    pub fn revoke_admin(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        if !AdminRegistry::revoke(&[self.data[0], self.data[1]]) {
+            return Err(ERROR_ADMIN_NOT_FOUND);
+        }
+        admin.store();
+        Ok(vec![])
+    }
+
+    // 设置暂停标志，需要管理员签名，data 为 标志位，0 表示全部恢复
+    pub fn set_paused(&self, pid: &[u64; 2]) -> Result<Vec<u64>, u32> {
+        let mut admin = ElfPlayer::get_from_pid(pid).ok_or(ERROR_PLAYER_NOT_EXIST)?;
+        admin.check_and_inc_nonce(self.nonce);
+        let flags = self.data[0];
+        if flags & !(PAUSE_ALL | PAUSE_WITHDRAW | PAUSE_PURCHASE) != 0 {
+            return Err(ERROR_INVALID_PAUSE_FLAGS);
+        }
+        *PAUSED.0.borrow_mut() = flags;
+        admin.store();
+        Ok(vec![flags])
+    }
+
+    // 暂停时拒绝玩家命令，查询、tick 和管理员命令不受影响
+    fn check_paused(&self) -> Result<(), u32> {
+        let paused = State::get_paused();
+        if paused & PAUSE_ALL != 0 {
+            return Err(ERROR_GAME_PAUSED);
+        }
+        if paused & PAUSE_WITHDRAW != 0 && self.command == WITHDRAW {
+            return Err(ERROR_WITHDRAW_PAUSED);
+        }
+        let purchase = matches!(self.command, BUY_ELF | BUY_PROP | BUY_SLOT | BUY_RANCH | UPGRADE_RANCH);
+        if paused & PAUSE_PURCHASE != 0 && purchase {
+            return Err(ERROR_PURCHASE_PAUSED);
+        }
+        Ok(())
+    }
+
+    // 是否是管理员命令，管理员命令不检查冻结状态，成功后记录操作
+    fn is_admin_command(&self) -> bool {
+        matches!(
+            self.command,
+            DEPOSIT
+                | DEPOSIT_GOLD
+                | SET_SEASON
+                | SET_CONFIG
+                | FREEZE_PLAYER
+                | UNFREEZE_PLAYER
+                | ADJUST_GOLD
+                | REMOVE_ELF
+                | RESTORE_ELF
+                | ADD_ADMIN
+                | REVOKE_ADMIN
+                | SET_PAUSED
+        )
+    }
+
     // 游戏进程
     pub fn process(&self, pkey: &[u64; 4], rand: &[u64; 4]) -> Vec<u64> {
         let rand = rand[0] ^ rand[1] ^ rand[2] ^ rand[3];
-        let b = match self.command {
-            INIT_PLAYER => self
-                .install_player(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            BUY_ELF => self
-                .buy_elf(&ElfPlayer::pkey_to_pid(&pkey), rand)
-                .map_or_else(|e| e, |_| 0),
-            COLLECT_GOLD => self
-                .collect_gold(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            CLEAN_RANCH => self
-                .clean_ranch(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            BUY_PROP => self
-                .buy_prop(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            FEED_ELF => self
-                .feed_elf(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            TREAT_ELF => self
-                .healing_elf(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            SELL_ELF => self
-                .sell_elf(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            BUY_SLOT => self
-                .buy_slot(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            WITHDRAW => self
-                .withdraw(&ElfPlayer::pkey_to_pid(&pkey))
-                .map_or_else(|e| e, |_| 0),
-            DEPOSIT => {
-                self.check_admin(pkey).map_or_else(|e| e, |_| 0);
-                self.deposit(&ElfPlayer::pkey_to_pid(&pkey))
-                    .map_or_else(|e| e, |_| 0)
+        // 交易成功后才推进种子链，抽卡在 tick 中用推进后的种子开奖
+        let seed = State::peek_next_seed(&ElfPlayer::pkey_to_pid(&pkey), self.nonce, rand);
+        // 玩家命令在游戏暂停时被拒绝，被冻结的玩家不能执行任何操作
+        if self.command != TIME_TICK && !self.is_admin_command() {
+            if let Err(e) = self.check_paused() {
+                return vec![e as u64];
             }
-
-            _ => {
-                self.check_admin(pkey).map_or_else(|e| e, |_| 0);
+            let player = ElfPlayer::get_from_pid(&ElfPlayer::pkey_to_pid(&pkey));
+            if player.map_or(false, |p| p.data.frozen) {
+                return vec![ERROR_PLAYER_FROZEN as u64];
+            }
+        }
+        let result = match self.command {
+            INIT_PLAYER => self.install_player(&ElfPlayer::pkey_to_pid(&pkey)),
+            BUY_ELF => self.buy_elf(&ElfPlayer::pkey_to_pid(&pkey)),
+            COLLECT_GOLD => self.collect_gold(&ElfPlayer::pkey_to_pid(&pkey)),
+            CLEAN_RANCH => self.clean_ranch(&ElfPlayer::pkey_to_pid(&pkey)),
+            BUY_PROP => self.buy_prop(&ElfPlayer::pkey_to_pid(&pkey)),
+            PLACE_PROP => self.place_prop(&ElfPlayer::pkey_to_pid(&pkey)),
+            USE_PROP => self.use_prop(&ElfPlayer::pkey_to_pid(&pkey)),
+            FEED_ELF => self.feed_elf(&ElfPlayer::pkey_to_pid(&pkey)),
+            TREAT_ELF => self.healing_elf(&ElfPlayer::pkey_to_pid(&pkey)),
+            SELL_ELF => self.sell_elf(&ElfPlayer::pkey_to_pid(&pkey)),
+            BUY_SLOT => self.buy_slot(&ElfPlayer::pkey_to_pid(&pkey)),
+            UPGRADE_RANCH => self.upgrade_ranch(&ElfPlayer::pkey_to_pid(&pkey)),
+            WITHDRAW => self.withdraw(&ElfPlayer::pkey_to_pid(&pkey)),
+            CLAIM_ACHIEVEMENT => self.claim_achievement(&ElfPlayer::pkey_to_pid(&pkey)),
+            CLAIM_QUEST => self.claim_quest(&ElfPlayer::pkey_to_pid(&pkey)),
+            CHECK_IN => self.check_in(&ElfPlayer::pkey_to_pid(&pkey)),
+            DEPOSIT_GOLD => self
+                .check_admin(pkey, ROLE_DEPOSITOR)
+                .and_then(|_| self.deposit_gold(&ElfPlayer::pkey_to_pid(&pkey))),
+            DEPOSIT => self
+                .check_admin(pkey, ROLE_DEPOSITOR)
+                .and_then(|_| self.deposit(&ElfPlayer::pkey_to_pid(&pkey))),
+            SET_SEASON => self
+                .check_admin(pkey, ROLE_CONFIG_MANAGER)
+                .and_then(|_| self.set_season(&ElfPlayer::pkey_to_pid(&pkey))),
+            SET_CONFIG => self
+                .check_admin(pkey, ROLE_CONFIG_MANAGER)
+                .and_then(|_| self.set_config(&ElfPlayer::pkey_to_pid(&pkey))),
+            FREEZE_PLAYER => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.set_player_frozen(&ElfPlayer::pkey_to_pid(&pkey), true)),
+            UNFREEZE_PLAYER => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.set_player_frozen(&ElfPlayer::pkey_to_pid(&pkey), false)),
+            ADJUST_GOLD => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.adjust_gold(&ElfPlayer::pkey_to_pid(&pkey))),
+            REMOVE_ELF => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.remove_elf(&ElfPlayer::pkey_to_pid(&pkey))),
+            RESTORE_ELF => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.restore_elf(&ElfPlayer::pkey_to_pid(&pkey))),
+            SET_PAUSED => self
+                .check_admin(pkey, ROLE_MODERATOR)
+                .and_then(|_| self.set_paused(&ElfPlayer::pkey_to_pid(&pkey))),
+            ADD_ADMIN => self
+                .check_root_admin(pkey)
+                .and_then(|_| self.add_admin(&ElfPlayer::pkey_to_pid(&pkey))),
+            REVOKE_ADMIN => self
+                .check_root_admin(pkey)
+                .and_then(|_| self.revoke_admin(&ElfPlayer::pkey_to_pid(&pkey))),
+            _ => self.check_admin(pkey, ROLE_TICKER).map(|_| {
                 zkwasm_rust_sdk::dbg!("monad k to run tick\n");
                 STATE.0.borrow_mut().queue.tick();
-                0
-            }
+                // 先推进种子，再用包含本次 tick 签名的种子给之前的购买开奖
+                State::set_seed(seed);
+                self.resolve_pending_elfs(&seed);
+                vec![]
+            }),
         };
-        vec![b as u64]
+        // 失败的交易不改变状态，也不推进种子链
+        if result.is_ok() {
+            State::set_seed(seed);
+        }
+        // 记录成功的管理员操作
+        if result.is_ok() && self.is_admin_command() {
+            let admin = ElfPlayer::pkey_to_pid(&pkey);
+            AuditRecord::new(self.command, admin, self.data.clone(), State::get_counter()).store();
+        }
+        // 成功时第一位为 0，后面是命令的结果；失败时只返回错误码
+        match result {
+            Ok(payload) => {
+                let mut ret = vec![0];
+                ret.extend(payload);
+                ret
+            }
+            Err(e) => vec![e as u64],
+        }
+    }
+
+    // 检查签名的管理员是否拥有命令需要的角色
+    pub fn check_admin(&self, pkey: &[u64; 4], role: u64) -> Result<(), u32> {
+        if AdminRegistry::get_roles(pkey) & role == 0 {
+            return Err(ERROR_MUST_ADMIN_KEY);
+        }
+        Ok(())
     }
 
-    pub fn check_admin(&self, pkey: &[u64; 4]) -> Result<(), u32> {
-        if *pkey != *ADMIN_PUBKEY {
+    pub fn check_root_admin(&self, pkey: &[u64; 4]) -> Result<(), u32> {
+        if !AdminRegistry::is_root(pkey) {
             return Err(ERROR_MUST_ADMIN_KEY);
         }
         Ok(())
@@ -676,27 +1462,160 @@ lazy_static::lazy_static! {
     pub static ref STATE: SafeState = SafeState (RefCell::new(State::new()));
 }
 
+// 暂停标志位，tick 中处理事件时 STATE 正在被借用，所以单独保存，由 State 负责持久化
+pub struct SafePaused(RefCell<u64>);
+unsafe impl Sync for SafePaused {}
+
+lazy_static! {
+    static ref PAUSED: SafePaused = SafePaused(RefCell::new(0));
+}
+
+// 状态扩展数据的 key，[0, 0, 0, 0] 中事件队列必须放在最后，新增的状态放这里
+const STATE_EXT_KEY: [u64; 4] = [0, 0, 0, 1];
+// 管理员修改的配置
+const CONFIG_KEY: [u64; 4] = [0, 0, 0, 2];
+// 根管理员添加的管理员
+const ADMIN_KEY: [u64; 4] = [0, 0, 0, 3];
+// 等待开奖的精灵购买
+const PENDING_ELF_KEY: [u64; 4] = [0, 0, 0, 4];
+
 pub struct State {
-    supplier: u64,
     queue: EventQueue<Event>,
+    // 随机数种子链，每笔成功的交易（包括 tick）推进一次：
+    // seed_n = poseidon(seed_n-1, pid[0], pid[1], nonce, rand)
+    // rand 是 process 收到的交易签名的 R 点（4 个 u64 异或），由签名的私钥持有者生成，
+    // 所以签名者自己可以反复签名挑选 rand，不能当作秘密的随机数。
+    // 所以抽卡分两步：BUY_ELF 只扣除金币、记录等待开奖的购买，之后的 tick 用管理员签名的 R 推进种子后，
+    // 按 poseidon(seed, pid[0], pid[1], 牧场id, 精灵id) 给每笔购买开奖。
+    // 购买者提交时不知道之后 tick 的签名，无法在本地算出品质再决定是否提交；
+    // 能影响开奖结果的只有 tick 的签名者，也就是运行 rollup 的管理员。
+    // 失败的交易不推进种子，种子链在证明中可以完整重放，结果可验证。
+    seed: [u64; 4],
+    treasury: u64, // 国库，收取提现手续费
 }
 
 impl State {
     pub fn new() -> Self {
         State {
-            supplier: 1000,
             queue: EventQueue::new(),
+            seed: [0, 0, 0, 0],
+            treasury: 0,
+        }
+    }
+
+    pub fn get_paused() -> u64 {
+        *PAUSED.0.borrow()
+    }
+
+    // 紧急暂停所有玩家操作时，事件中也不扣除维护费
+    pub fn is_game_paused() -> bool {
+        State::get_paused() & PAUSE_ALL != 0
+    }
+
+    // 国库增加金币
+    pub fn add_treasury(amount: u64) {
+        STATE.0.borrow_mut().treasury += amount;
+    }
+
+    // 计算种子链的下一个种子，不修改状态
+    pub fn peek_next_seed(pid: &[u64; 2], nonce: u64, rand: u64) -> [u64; 4] {
+        let state = STATE.0.borrow();
+        let mut hasher = PoseidonHasher::new();
+        for s in state.seed {
+            hasher.update(s);
         }
+        hasher.update(pid[0]);
+        hasher.update(pid[1]);
+        hasher.update(nonce);
+        hasher.update(rand);
+        hasher.finalize()
     }
+
+    // 交易成功后推进种子链
+    pub fn set_seed(seed: [u64; 4]) {
+        STATE.0.borrow_mut().seed = seed;
+    }
+
+    // 开奖的随机数，同一次 tick 中每个玩家、每只新精灵都不同
+    pub fn get_draw_rand(seed: &[u64; 4], pid: &[u64; 2], ranch_id: u64, elf_id: u64) -> u64 {
+        let mut hasher = PoseidonHasher::new();
+        for s in seed {
+            hasher.update(*s);
+        }
+        hasher.update(pid[0]);
+        hasher.update(pid[1]);
+        hasher.update(ranch_id);
+        hasher.update(elf_id);
+        hasher.finalize()[0]
+    }
+    pub fn get_counter() -> u64 {
+        STATE.0.borrow().queue.counter
+    }
+
+    // 快照包含国库和金币总量统计，用于监控经济数据
     pub fn snapshot() -> String {
-        let counter = STATE.0.borrow().queue.counter;
-        serde_json::to_string(&counter).unwrap()
+        let state = STATE.0.borrow();
+        let snapshot = serde_json::json!({
+            "counter": state.queue.counter,
+            "treasury": state.treasury,
+            "supply": Supply::get(),
+            "paused": State::get_paused(),
+        });
+        serde_json::to_string(&snapshot).unwrap()
     }
     pub fn get_state(pkey: Vec<u64>) -> String {
         let player = ElfPlayer::get_from_pid(&ElfPlayer::pkey_to_pid(&pkey.try_into().unwrap()));
         serde_json::to_string(&player).unwrap()
     }
 
+    // 查询玩家最近的动态，最新的在前
+    pub fn get_activities(pkey: Vec<u64>) -> String {
+        let pid = ElfPlayer::pkey_to_pid(&pkey.try_into().unwrap());
+        serde_json::to_string(&Activity::get_activities(&pid)).unwrap()
+    }
+
+    // 查询玩家等待开奖的精灵购买
+    pub fn get_pending_elfs(pkey: Vec<u64>) -> String {
+        let pid = ElfPlayer::pkey_to_pid(&pkey.try_into().unwrap());
+        serde_json::to_string(&PendingElf::get_by_pid(&pid)).unwrap()
+    }
+
+    // 查询当天开放的每日任务
+    pub fn get_daily_quests() -> String {
+        let day = State::get_counter() / TICKS_PER_DAY;
+        serde_json::to_string(&Quest::get_daily_quests(day)).unwrap()
+    }
+
+    // 查询排行榜
+    pub fn get_leaderboard(board_type: u64) -> String {
+        serde_json::to_string(&Leaderboard::get_entries(board_type)).unwrap()
+    }
+
+    // 所有管理员和角色
+    pub fn get_admins() -> String {
+        serde_json::to_string(&AdminRegistry::get_all()).unwrap()
+    }
+
+    // 按操作顺序查询管理员操作记录
+    pub fn get_audit_records(start: u64, limit: u64) -> String {
+        let count = AuditRecord::get_count();
+        let records = AuditRecord::get_records(start, limit);
+        serde_json::to_string(&(count, records)).unwrap()
+    }
+
+    // 查询 L1 充值是否已处理
+    pub fn get_deposit(tx_hash: Vec<u64>) -> String {
+        let record = DepositRecord::get_record(&tx_hash.try_into().unwrap());
+        serde_json::to_string(&record).unwrap()
+    }
+
+    // 按处理顺序查询已处理的充值，用于对账
+    pub fn get_deposits(start: u64, limit: u64) -> String {
+        let count = DepositRecord::get_count();
+        let records = DepositRecord::get_records(start, limit);
+        serde_json::to_string(&(count, records)).unwrap()
+    }
+
     pub fn preempt() -> bool {
         let counter = STATE.0.borrow().queue.counter;
         if counter % 32 == 0 {
@@ -711,7 +1630,7 @@ impl State {
     }
 
     pub fn rand_seed() -> u64 {
-        0
+        STATE.0.borrow().seed[0]
     }
 
     pub fn hash_event_contains(event: Event) -> bool {
@@ -723,10 +1642,26 @@ impl State {
     pub fn store() {
         let mut state = STATE.0.borrow_mut();
         let mut v = Vec::with_capacity(state.queue.list.len() + 8);
-        v.push(state.supplier);
         state.queue.to_data(&mut v);
         let kvpair = unsafe { &mut MERKLE_MAP };
         kvpair.set(&[0, 0, 0, 0], v.as_slice());
+        // 扩展状态
+        let mut ext = vec![];
+        ext.extend_from_slice(&state.seed);
+        ext.push(state.treasury);
+        Supply::to_data(&mut ext);
+        Season::to_data(&mut ext);
+        ext.push(State::get_paused());
+        kvpair.set(&STATE_EXT_KEY, ext.as_slice());
+        let mut config = vec![];
+        ConfigOverride::to_data(&mut config);
+        kvpair.set(&CONFIG_KEY, config.as_slice());
+        let mut admins = vec![];
+        AdminRegistry::to_data(&mut admins);
+        kvpair.set(&ADMIN_KEY, admins.as_slice());
+        let mut pending = vec![];
+        PendingElf::to_data(&mut pending);
+        kvpair.set(&PENDING_ELF_KEY, pending.as_slice());
         state.queue.store();
         let root = kvpair.merkle.root.clone();
         zkwasm_rust_sdk::dbg!("root after store: {:?}\n", root);
@@ -735,10 +1670,30 @@ impl State {
         let mut state = STATE.0.borrow_mut();
         let kvpair = unsafe { &mut MERKLE_MAP };
         let mut data = kvpair.get(&[0, 0, 0, 0]);
+        let mut ext = kvpair.get(&STATE_EXT_KEY);
         if !data.is_empty() {
             let mut data = data.iter_mut();
-            state.supplier = *data.next().unwrap();
+            // 没有扩展状态的是最初版本的数据，事件队列前有一个没有使用的 supplier，跳过
+            if ext.is_empty() {
+                data.next();
+            }
             state.queue = EventQueue::from_data(&mut data);
         }
+        if !ext.is_empty() {
+            let mut ext = ext.iter_mut();
+            for s in state.seed.iter_mut() {
+                *s = *ext.next().unwrap();
+            }
+            state.treasury = *ext.next().unwrap();
+            Supply::from_data(&mut ext);
+            Season::from_data(&mut ext);
+            *PAUSED.0.borrow_mut() = *ext.next().unwrap();
+        }
+        let mut config = kvpair.get(&CONFIG_KEY);
+        ConfigOverride::from_data(&mut config.iter_mut());
+        let mut admins = kvpair.get(&ADMIN_KEY);
+        AdminRegistry::from_data(&mut admins.iter_mut());
+        let mut pending = kvpair.get(&PENDING_ELF_KEY);
+        PendingElf::from_data(&mut pending.iter_mut());
     }
 }
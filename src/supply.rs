@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::slice::IterMut;
+
+// 金币总量统计，用于监控通胀和发现异常
+// 产出金币发生在事件处理中，此时 STATE 正在被 tick 借用，所以单独保存，由 State 负责持久化
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Supply {
+    pub minted: u64,        // 精灵产出的金币
+    pub deposited: u64,     // L1 充值的金币
+    pub burned: u64,        // 购买精灵、道具、槽位和升级牧场消耗的金币
+    pub sold: u64,          // 卖出精灵获得的金币
+    pub withdrawn: u64,     // 提现的金币（含手续费）
+    pub rewarded: u64,      // 成就等奖励发放的金币
+    pub upkeep: u64,        // 牧场维护费扣除的金币
+    pub admin_minted: u64,  // 管理员调整增加的金币
+    pub admin_burned: u64,  // 管理员调整扣除的金币
+}
+
+pub struct SafeSupply(RefCell<Supply>);
+unsafe impl Sync for SafeSupply {}
+
+lazy_static::lazy_static! {
+    pub static ref SUPPLY: SafeSupply = SafeSupply(RefCell::new(Supply::default()));
+}
+
+impl Supply {
+    pub fn get() -> Supply {
+        SUPPLY.0.borrow().clone()
+    }
+
+    pub fn mint(amount: u64) {
+        SUPPLY.0.borrow_mut().minted += amount;
+    }
+
+    pub fn deposit(amount: u64) {
+        SUPPLY.0.borrow_mut().deposited += amount;
+    }
+
+    pub fn burn(amount: u64) {
+        SUPPLY.0.borrow_mut().burned += amount;
+    }
+
+    pub fn sell(amount: u64) {
+        SUPPLY.0.borrow_mut().sold += amount;
+    }
+
+    pub fn withdraw(amount: u64) {
+        SUPPLY.0.borrow_mut().withdrawn += amount;
+    }
+
+    pub fn reward(amount: u64) {
+        SUPPLY.0.borrow_mut().rewarded += amount;
+    }
+
+    pub fn upkeep(amount: u64) {
+        SUPPLY.0.borrow_mut().upkeep += amount;
+    }
+
+    pub fn admin_mint(amount: u64) {
+        SUPPLY.0.borrow_mut().admin_minted += amount;
+    }
+
+    pub fn admin_burn(amount: u64) {
+        SUPPLY.0.borrow_mut().admin_burned += amount;
+    }
+
+    pub fn to_data(data: &mut Vec<u64>) {
+        let supply = SUPPLY.0.borrow();
+        data.push(supply.minted);
+        data.push(supply.deposited);
+        data.push(supply.burned);
+        data.push(supply.sold);
+        data.push(supply.withdrawn);
+        data.push(supply.rewarded);
+        data.push(supply.upkeep);
+        data.push(supply.admin_minted);
+        data.push(supply.admin_burned);
+    }
+
+    pub fn from_data(u64data: &mut IterMut<u64>) {
+        let mut supply = SUPPLY.0.borrow_mut();
+        supply.minted = *u64data.next().unwrap();
+        supply.deposited = *u64data.next().unwrap();
+        supply.burned = *u64data.next().unwrap();
+        supply.sold = *u64data.next().unwrap();
+        supply.withdrawn = *u64data.next().unwrap();
+        supply.rewarded = *u64data.next().unwrap();
+        supply.upkeep = *u64data.next().unwrap();
+        supply.admin_minted = *u64data.next().unwrap();
+        supply.admin_burned = *u64data.next().unwrap();
+    }
+}
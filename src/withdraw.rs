@@ -0,0 +1,72 @@
+use serde::Serialize;
+
+// 提现规则
+#[derive(Clone, Debug, Serialize)]
+pub struct WithdrawPolicy {
+    pub min_amount: u64, // 单次最少提现金币
+    pub fee_rate: u64,   // 手续费率，以 10000 为 100%，手续费进入国库
+    pub daily_cap: u64,  // 每个玩家每天最多提现金币
+}
+
+impl WithdrawPolicy {
+    pub fn get_policy() -> &'static WithdrawPolicy {
+        &*WITHDRAW_POLICY
+    }
+
+    // 计算提现手续费
+    pub fn compute_fee(&self, amount: u64) -> u64 {
+        amount * self.fee_rate / 10000
+    }
+
+    // 计算本次提现后当天已提现的金币，跨天重新计算，超过每日限额返回 None
+    pub fn get_withdrawn_after(&self, withdraw_day: u64, withdraw_today: u64, day: u64, amount: u64) -> Option<u64> {
+        let withdrawn = if withdraw_day == day { withdraw_today } else { 0 };
+        withdrawn
+            .checked_add(amount)
+            .filter(|total| *total <= self.daily_cap)
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref WITHDRAW_POLICY: WithdrawPolicy = WithdrawPolicy {
+        min_amount: 1000,
+        fee_rate: 500, // 5%
+        daily_cap: 100000,
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_goes_by_rate() {
+        let policy = WithdrawPolicy::get_policy();
+        assert_eq!(policy.compute_fee(10000), 500);
+        assert_eq!(policy.compute_fee(1000), 50);
+        assert_eq!(policy.compute_fee(19), 0);
+    }
+
+    #[test]
+    fn daily_cap_accumulates_within_a_day() {
+        let policy = WithdrawPolicy::get_policy();
+        assert_eq!(policy.get_withdrawn_after(3, 0, 3, 40000), Some(40000));
+        assert_eq!(policy.get_withdrawn_after(3, 40000, 3, 60000), Some(100000));
+        assert_eq!(policy.get_withdrawn_after(3, 40000, 3, 60001), None);
+        assert_eq!(policy.get_withdrawn_after(3, 100000, 3, 1000), None);
+    }
+
+    #[test]
+    fn daily_cap_resets_on_a_new_day() {
+        let policy = WithdrawPolicy::get_policy();
+        assert_eq!(policy.get_withdrawn_after(3, 100000, 4, 100000), Some(100000));
+        assert_eq!(policy.get_withdrawn_after(3, 100000, 4, 100001), None);
+    }
+
+    #[test]
+    fn daily_cap_rejects_overflow() {
+        let policy = WithdrawPolicy::get_policy();
+        assert_eq!(policy.get_withdrawn_after(3, 1, 3, u64::MAX), None);
+    }
+}
@@ -14,6 +14,16 @@ function bytesToHex(bytes: Array<number>): string  {
     return Array.from(bytes, byte => byte.toString(16).padStart(2, '0')).join('');
 }
 
+// L1 transaction hash (32 bytes hex) as 4 u64 limbs, big endian, limb 0 holds the highest bytes
+function txHashToParams(txHash: string): Array<bigint> {
+  let bytes = new BN(txHash.replace(/^0x/, ""), 16).toArray("be", 32);
+  let limbs = [];
+  for (let i = 0; i < 32; i += 8) {
+    limbs.push(BigInt('0x' + bytesToHex(bytes.slice(i, i + 8))));
+  }
+  return limbs;
+}
+
 function addrToParams(bn: BN): Array<bigint> {
   // address is encoded in BigEndian
   const mask = new BN('ffffffffffffffff', 16);
@@ -32,8 +42,16 @@ const CMD_SELL_ELF = 6n;
 const CMD_CLEAN_RANCH = 4n;
 const CMD_COLLECT_GOLD = 11n;
 const CMD_WITHDRAW = 7n;
+const CMD_DEPOSIT = 8n; // admin only
+const CMD_DEPOSIT_GOLD = 16n; // admin only
 const CMD_PROP = 12n;
 const CMD_BUY_SLOT = 13n;
+const CMD_PLACE_PROP = 14n;
+const CMD_USE_PROP = 15n;
+const CMD_CLAIM_ACHIEVEMENT = 17n;
+const CMD_CLAIM_QUEST = 18n;
+const CMD_CHECK_IN = 19n;
+const CMD_UPGRADE_RANCH = 30n;
 
 const CMD_FEED_ELF = 3n; // 喂食精灵
 const  CMD_TREAT_ELF = 5n; // 治疗宠物
@@ -157,12 +175,12 @@ export class Player {
     }
   }
 
-  async buy_prop( ranch_id: bigint,prop_type:bigint) {
+  async buy_prop(prop_type:bigint, count:bigint = 1n) {
     let nonce = await this.getNonce();
     console.log("nonce :",nonce)
     try {
       let finished = await rpc.sendTransaction(
-          new BigUint64Array([createCommand(nonce, CMD_PROP, 0n), ranch_id, prop_type, 0n]),
+          new BigUint64Array([createCommand(nonce, CMD_PROP, 0n), prop_type, count, 0n]),
           this.processingKey
       );
       console.log("buy_prop processed at:", finished);
@@ -170,7 +188,24 @@ export class Player {
       if(e instanceof Error) {
         console.log(e.message);
       }
-      console.log("buy_prop error at ranch_id:", ranch_id, "prop_type :", prop_type);
+      console.log("buy_prop error at prop_type :", prop_type, "count :", count);
+    }
+  }
+
+  async place_prop( ranch_id: bigint,prop_type:bigint) {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_PLACE_PROP, 0n), ranch_id, prop_type, 0n]),
+          this.processingKey
+      );
+      console.log("place_prop processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("place_prop error at ranch_id:", ranch_id, "prop_type :", prop_type);
     }
   }
 
@@ -191,6 +226,23 @@ export class Player {
     }
   }
 
+  async upgrade_ranch( ranch_id: bigint) {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_UPGRADE_RANCH, 0n), ranch_id, 0n, 0n]),
+          this.processingKey
+      );
+      console.log("upgrade_ranch processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("upgrade_ranch error at ranch_id:", ranch_id);
+    }
+  }
+
   async feed_elf( ranch_id: bigint,elf_id:bigint,prop_type:bigint) {
     let nonce = await this.getNonce();
     console.log("nonce :",nonce)
@@ -208,6 +260,74 @@ export class Player {
     }
   }
 
+  async use_prop( ranch_id: bigint,elf_id:bigint,prop_type:bigint) {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_USE_PROP, 0n), ranch_id, elf_id, prop_type]),
+          this.processingKey
+      );
+      console.log("use_prop processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("use_prop error at ranch_id:", ranch_id, "prop_type :", prop_type);
+    }
+  }
+
+  async claim_achievement(achievement_id: bigint) {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_CLAIM_ACHIEVEMENT, 0n), achievement_id, 0n, 0n]),
+          this.processingKey
+      );
+      console.log("claim_achievement processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("claim_achievement error at achievement_id:", achievement_id);
+    }
+  }
+
+  async claim_quest(quest_id: bigint) {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_CLAIM_QUEST, 0n), quest_id, 0n, 0n]),
+          this.processingKey
+      );
+      console.log("claim_quest processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("claim_quest error at quest_id:", quest_id);
+    }
+  }
+
+  async check_in() {
+    let nonce = await this.getNonce();
+    console.log("nonce :",nonce)
+    try {
+      let finished = await rpc.sendTransaction(
+          new BigUint64Array([createCommand(nonce, CMD_CHECK_IN, 0n), 0n, 0n, 0n]),
+          this.processingKey
+      );
+      console.log("check_in processed at:", finished);
+    } catch(e) {
+      if(e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("check_in error");
+    }
+  }
+
   async treat_elf( ranch_id: bigint,elf_id:bigint,prop_type:bigint) {
     let nonce = await this.getNonce();
     console.log("nonce :",nonce)
@@ -232,6 +352,54 @@ export class Player {
 
 
 
+  /*
+    admin only, credit a USDT prop purchase paid on L1
+    [cmd, pid[0], pid[1], (32 bit paid amount | 16 bit count | 16 bit prop_type), tx hash limb 0..3]
+       */
+  async deposit(pid: Array<bigint>, prop_type: bigint, count: bigint, paid_amount: bigint, txHash: string) {
+    let nonce = await this.getNonce();
+    try {
+      let finished = await rpc.sendTransaction(
+        new BigUint64Array([
+          createCommand(nonce, CMD_DEPOSIT, 0n),
+          pid[0],
+          pid[1],
+          (paid_amount << 32n) + (count << 16n) + prop_type,
+          ...txHashToParams(txHash),
+        ]), this.processingKey);
+      console.log("deposit processed at:", finished);
+    } catch(e) {
+      if (e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("deposit error at tx:", txHash);
+    }
+  }
+
+  /*
+    admin only, credit gold paid on L1
+    [cmd, pid[0], pid[1], amount, tx hash limb 0..3]
+       */
+  async deposit_gold(pid: Array<bigint>, amount: bigint, txHash: string) {
+    let nonce = await this.getNonce();
+    try {
+      let finished = await rpc.sendTransaction(
+        new BigUint64Array([
+          createCommand(nonce, CMD_DEPOSIT_GOLD, 0n),
+          pid[0],
+          pid[1],
+          amount,
+          ...txHashToParams(txHash),
+        ]), this.processingKey);
+      console.log("deposit_gold processed at:", finished);
+    } catch(e) {
+      if (e instanceof Error) {
+        console.log(e.message);
+      }
+      console.log("deposit_gold error at tx:", txHash);
+    }
+  }
+
   /*
     (32 bit amount | 32 bit highbit of address)
     (64 bit mid bit of address (be))
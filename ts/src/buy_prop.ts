@@ -5,7 +5,7 @@ let player = new Player(account);
 
 async function main() {
 
-    await player.buy_prop(1n,4n)
+    await player.buy_prop(4n)
     console.log("buy_prop ");
 }
 
@@ -6,7 +6,7 @@ let player = new Player(account);
 
 async function main() {
 
-    await player.treat_elf(1n,1n,1n)
+    await player.treat_elf(1n,1n,6n)
     console.log("treat_elf ");
 }
 